@@ -0,0 +1,169 @@
+//! Self-describing binary delta encoding for attachments.
+//!
+//! Re-exported attachments (e.g. an image round-tripped through another
+//! app) rarely align on text-line boundaries, so [`crate::diff`] doesn't
+//! help here. This wraps [`crate::delta`]'s rsync-style block matcher
+//! with a small header recording the expected source length and content
+//! hash, so [`apply_binary_delta`] can refuse to apply a patch against
+//! the wrong base instead of silently producing garbage.
+
+use wasm_bindgen::prelude::*;
+
+use crate::delta::{apply_delta, compute_delta};
+use crate::error::SyncError;
+use crate::hashing::content_hash;
+
+/// Encode a binary delta that turns `old` into `new`.
+///
+/// The patch is self-describing: it carries `old`'s length and content
+/// hash so [`apply_binary_delta`] can validate it's being applied against
+/// the right base before trusting the body. When `old` is empty the
+/// patch is effectively `new` in full (there's nothing to diff against).
+#[wasm_bindgen]
+pub fn binary_delta(old: &[u8], new: &[u8]) -> Vec<u8> {
+    let hash = content_hash(old);
+    let hash_bytes = hash.as_bytes();
+
+    let mut patch = Vec::new();
+    patch.extend_from_slice(&(old.len() as u64).to_le_bytes());
+    patch.extend_from_slice(&(hash_bytes.len() as u32).to_le_bytes());
+    patch.extend_from_slice(hash_bytes);
+    patch.extend_from_slice(&compute_delta(old, new));
+    patch
+}
+
+/// Reconstruct `new` from `old` and a patch produced by [`binary_delta`].
+///
+/// Returns an error if `patch` is truncated, or if `old` doesn't match
+/// the source length/hash recorded in the patch header.
+#[wasm_bindgen]
+pub fn apply_binary_delta(old: &[u8], patch: &[u8]) -> Result<Vec<u8>, SyncError> {
+    crate::guard(|| apply_binary_delta_inner(old, patch))?
+}
+
+fn apply_binary_delta_inner(old: &[u8], patch: &[u8]) -> Result<Vec<u8>, SyncError> {
+    let source_len = patch
+        .get(0..8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| SyncError::CorruptDelta("truncated binary delta: missing source length".to_string()))?;
+    let hash_len = patch
+        .get(8..12)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()) as usize)
+        .ok_or_else(|| SyncError::CorruptDelta("truncated binary delta: missing hash length".to_string()))?;
+
+    let hash_start = 12;
+    let hash_end = hash_start + hash_len;
+    let stored_hash = patch
+        .get(hash_start..hash_end)
+        .ok_or_else(|| SyncError::CorruptDelta("truncated binary delta: missing source hash".to_string()))?;
+    let stored_hash = std::str::from_utf8(stored_hash)
+        .map_err(|_| SyncError::CorruptDelta("corrupt binary delta: source hash is not valid UTF-8".to_string()))?;
+
+    if old.len() as u64 != source_len || content_hash(old) != stored_hash {
+        return Err(SyncError::CorruptDelta(
+            "binary delta does not apply: source buffer doesn't match the patch's recorded base".to_string(),
+        ));
+    }
+
+    apply_delta(old, &patch[hash_end..])
+        .map_err(|err| SyncError::CorruptDelta(err.as_string().unwrap_or_else(|| "binary delta patch failed to apply".to_string())))
+}
+
+/// Apply `patch` to `old` as [`apply_binary_delta`] does, then check the
+/// result's [`content_hash`] against `expected_hash` before returning it.
+/// The patch header already guards against applying to the wrong
+/// *source*; this additionally guards against the reconstructed *result*
+/// coming out wrong despite that check passing.
+#[wasm_bindgen]
+pub fn apply_binary_delta_verified(old: &[u8], patch: &[u8], expected_hash: &str) -> Result<Vec<u8>, SyncError> {
+    let result = apply_binary_delta(old, patch)?;
+    let actual_hash = content_hash(&result);
+    if actual_hash != expected_hash {
+        return Err(SyncError::HashMismatch(format!(
+            "expected content hash {expected_hash}, got {actual_hash}"
+        )));
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn pseudo_random_bytes(len: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state & 0xff) as u8
+            })
+            .collect()
+    }
+
+    #[wasm_bindgen_test]
+    fn round_trips_random_buffers() {
+        let old = pseudo_random_bytes(4096, 1);
+        let mut new = old.clone();
+        new.splice(1000..1010, pseudo_random_bytes(20, 2));
+
+        let patch = binary_delta(&old, &new);
+        let rebuilt = apply_binary_delta(&old, &patch).unwrap();
+        assert_eq!(rebuilt, new);
+    }
+
+    #[wasm_bindgen_test]
+    fn delta_is_smaller_than_new_when_files_mostly_overlap() {
+        let old = pseudo_random_bytes(20_000, 3);
+        let mut new = old.clone();
+        new.splice(5000..5010, pseudo_random_bytes(10, 4));
+
+        let patch = binary_delta(&old, &new);
+        assert!(patch.len() < new.len());
+    }
+
+    #[wasm_bindgen_test]
+    fn empty_old_delta_reconstructs_new_in_full() {
+        let new = pseudo_random_bytes(256, 5);
+        let patch = binary_delta(&[], &new);
+        let rebuilt = apply_binary_delta(&[], &patch).unwrap();
+        assert_eq!(rebuilt, new);
+    }
+
+    #[wasm_bindgen_test]
+    fn applying_against_the_wrong_source_is_rejected() {
+        let old = pseudo_random_bytes(1024, 6);
+        let new = pseudo_random_bytes(1024, 7);
+        let patch = binary_delta(&old, &new);
+
+        let wrong_old = pseudo_random_bytes(1024, 8);
+        assert!(apply_binary_delta(&wrong_old, &patch).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn verified_apply_accepts_a_correct_expected_hash() {
+        let old = pseudo_random_bytes(512, 9);
+        let mut new = old.clone();
+        new.splice(100..110, pseudo_random_bytes(10, 10));
+
+        let patch = binary_delta(&old, &new);
+        let expected_hash = content_hash(&new);
+        assert_eq!(apply_binary_delta_verified(&old, &patch, &expected_hash).unwrap(), new);
+    }
+
+    #[wasm_bindgen_test]
+    fn verified_apply_rejects_a_wrong_expected_hash() {
+        let old = pseudo_random_bytes(512, 11);
+        let mut new = old.clone();
+        new.splice(100..110, pseudo_random_bytes(10, 12));
+
+        let patch = binary_delta(&old, &new);
+        let wrong_hash = content_hash(b"not the real result");
+        match apply_binary_delta_verified(&old, &patch, &wrong_hash) {
+            Err(SyncError::HashMismatch(message)) => assert!(message.contains(&wrong_hash)),
+            other => panic!("expected Err(SyncError::HashMismatch(_)), got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Content-addressed hashing for vault sync change detection.
+//!
+//! Replaces the old demo hash in `compute_hash` with a real,
+//! collision-resistant digest (BLAKE3) so the sync engine can tell which
+//! notes changed without re-uploading everything.
+
+use wasm_bindgen::prelude::*;
+
+/// Compute a stable content ID for `bytes`.
+///
+/// Returns the BLAKE3 digest of `bytes` as a lowercase hex string, suitable
+/// as a dedup/sync index key.
+#[wasm_bindgen]
+pub fn content_id(bytes: &[u8]) -> String {
+    blake3::hash(bytes).to_hex().to_string()
+}
+
+/// Incremental BLAKE3 hasher for streaming large notes or attachments in
+/// from JS a chunk at a time instead of buffering the whole file first.
+#[wasm_bindgen]
+pub struct ContentHasher {
+    inner: blake3::Hasher,
+}
+
+#[wasm_bindgen]
+impl ContentHasher {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> ContentHasher {
+        ContentHasher {
+            inner: blake3::Hasher::new(),
+        }
+    }
+
+    /// Feed the next chunk of bytes into the running hash.
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.inner.update(chunk);
+    }
+
+    /// Consume the hasher and return the final content ID as hex.
+    pub fn finalize(self) -> String {
+        self.inner.finalize().to_hex().to_string()
+    }
+}
+
+impl Default for ContentHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn content_id_is_stable_and_distinguishes_input() {
+        assert_eq!(content_id(b"test"), content_id(b"test"));
+        assert_ne!(content_id(b"test"), content_id(b"different"));
+    }
+
+    #[wasm_bindgen_test]
+    fn incremental_hash_matches_one_shot() {
+        let mut hasher = ContentHasher::new();
+        hasher.update(b"te");
+        hasher.update(b"st");
+        assert_eq!(hasher.finalize(), content_id(b"test"));
+    }
+}
@@ -0,0 +1,200 @@
+//! A Bloom filter of chunk hashes, so a client can ask "does the remote
+//! probably already have this chunk?" against a small, locally-cached
+//! filter instead of shipping the remote's full hash set across the
+//! network.
+//!
+//! A `contains` hit can be a false positive (the item may not actually
+//! be present, so it's still worth double-checking with the remote
+//! before skipping an upload), but a miss is definitive: the item is
+//! guaranteed absent, so it's always safe to upload.
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::SyncError;
+
+/// A Bloom filter, sized for an expected item count and target false
+/// positive rate.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BloomFilter {
+    bits: Vec<u8>,
+    num_bits: u64,
+    num_hashes: u32,
+}
+
+#[wasm_bindgen]
+impl BloomFilter {
+    /// Size a new, empty filter for `expected_items` insertions at
+    /// roughly `false_positive_rate` (e.g. `0.01` for 1%).
+    #[wasm_bindgen(constructor)]
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> BloomFilter {
+        let n = (expected_items.max(1)) as f64;
+        let p = false_positive_rate.clamp(f64::MIN_POSITIVE, 0.999);
+
+        // Standard optimal sizing: m bits and k hash functions minimizing
+        // the false positive rate for n expected items.
+        let num_bits = (-(n * p.ln()) / std::f64::consts::LN_2.powi(2)).ceil().max(8.0) as u64;
+        let num_hashes = ((num_bits as f64 / n) * std::f64::consts::LN_2).round().max(1.0) as u32;
+
+        BloomFilter { bits: vec![0u8; (num_bits as usize).div_ceil(8)], num_bits, num_hashes }
+    }
+
+    /// Add `item` to the filter.
+    pub fn insert(&mut self, item: &str) {
+        let indices: Vec<u64> = self.bit_indices(item).collect();
+        for index in indices {
+            let (byte, bit) = (index / 8, index % 8);
+            self.bits[byte as usize] |= 1 << bit;
+        }
+    }
+
+    /// Whether `item` might have been inserted. `false` is definitive; `true`
+    /// can be a false positive.
+    pub fn contains(&self, item: &str) -> bool {
+        self.bit_indices(item).all(|index| {
+            let (byte, bit) = (index / 8, index % 8);
+            self.bits[byte as usize] & (1 << bit) != 0
+        })
+    }
+
+    /// Serialize to a compact byte representation for shipping to a peer.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(12 + self.bits.len());
+        out.extend_from_slice(&self.num_bits.to_le_bytes());
+        out.extend_from_slice(&self.num_hashes.to_le_bytes());
+        out.extend_from_slice(&self.bits);
+        out
+    }
+
+    /// Parse a filter previously produced by [`BloomFilter::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<BloomFilter, SyncError> {
+        let num_bits = bytes
+            .get(0..8)
+            .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+            .ok_or_else(|| SyncError::CorruptData("truncated bloom filter: missing bit count".to_string()))?;
+        let num_hashes = bytes
+            .get(8..12)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+            .ok_or_else(|| SyncError::CorruptData("truncated bloom filter: missing hash count".to_string()))?;
+        let bits = bytes.get(12..).unwrap_or(&[]).to_vec();
+
+        if num_bits == 0 || num_hashes == 0 {
+            return Err(SyncError::CorruptData(format!(
+                "corrupt bloom filter: num_bits and num_hashes must both be nonzero, got num_bits={num_bits} num_hashes={num_hashes}"
+            )));
+        }
+
+        let expected_bytes = (num_bits as usize).div_ceil(8);
+        if bits.len() != expected_bytes {
+            return Err(SyncError::CorruptData(format!(
+                "corrupt bloom filter: expected {expected_bytes} bytes of bits for {num_bits} bits, found {}",
+                bits.len()
+            )));
+        }
+        Ok(BloomFilter { bits, num_bits, num_hashes })
+    }
+
+    /// The size of the underlying bit array.
+    #[wasm_bindgen(getter)]
+    pub fn num_bits(&self) -> u64 {
+        self.num_bits
+    }
+
+    /// The number of hash functions used per item.
+    #[wasm_bindgen(getter)]
+    pub fn num_hashes(&self) -> u32 {
+        self.num_hashes
+    }
+
+    /// The `num_hashes` bit positions `item` maps to, derived from a single
+    /// BLAKE3 digest via Kirsch–Mitzenmacher double hashing instead of
+    /// computing `num_hashes` independent hashes.
+    fn bit_indices(&self, item: &str) -> impl Iterator<Item = u64> + '_ {
+        let digest = blake3::hash(item.as_bytes());
+        let bytes = digest.as_bytes();
+        let h1 = u64::from_le_bytes(bytes[0..8].try_into().unwrap());
+        let h2 = u64::from_le_bytes(bytes[8..16].try_into().unwrap());
+        (0..self.num_hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn every_inserted_item_is_reported_present() {
+        let mut filter = BloomFilter::new(1000, 0.01);
+        let items: Vec<String> = (0..1000).map(|i| format!("chunk-{i}")).collect();
+        for item in &items {
+            filter.insert(item);
+        }
+        for item in &items {
+            assert!(filter.contains(item), "expected {item} to be present");
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn false_positive_rate_is_roughly_within_target() {
+        let target_fpr = 0.01;
+        let n = 2000;
+        let mut filter = BloomFilter::new(n, target_fpr);
+        for i in 0..n {
+            filter.insert(&format!("present-{i}"));
+        }
+
+        let trials = 20_000;
+        let false_positives = (0..trials).filter(|i| filter.contains(&format!("absent-{i}"))).count();
+        let observed_fpr = false_positives as f64 / trials as f64;
+
+        // Bloom filter FPR is probabilistic, not exact; allow a generous
+        // margin (5x target) so the test isn't flaky while still catching
+        // a badly mis-sized filter.
+        assert!(observed_fpr < target_fpr * 5.0, "observed FPR {observed_fpr} far exceeds target {target_fpr}");
+    }
+
+    #[wasm_bindgen_test]
+    fn to_bytes_round_trips_through_from_bytes() {
+        let mut filter = BloomFilter::new(100, 0.01);
+        filter.insert("alpha");
+        filter.insert("beta");
+
+        let bytes = filter.to_bytes();
+        let reparsed = BloomFilter::from_bytes(&bytes).unwrap();
+
+        assert_eq!(reparsed, filter);
+        assert!(reparsed.contains("alpha"));
+        assert!(reparsed.contains("beta"));
+    }
+
+    #[wasm_bindgen_test]
+    fn from_bytes_rejects_truncated_input() {
+        match BloomFilter::from_bytes(&[1, 2, 3]) {
+            Err(SyncError::CorruptData(message)) => assert!(message.contains("truncated")),
+            other => panic!("expected Err(SyncError::CorruptData(_)), got {other:?}"),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn from_bytes_rejects_a_bit_array_of_the_wrong_length() {
+        let filter = BloomFilter::new(100, 0.01);
+        let mut bytes = filter.to_bytes();
+        bytes.pop();
+        match BloomFilter::from_bytes(&bytes) {
+            Err(SyncError::CorruptData(message)) => assert!(message.contains("corrupt bloom filter")),
+            other => panic!("expected Err(SyncError::CorruptData(_)), got {other:?}"),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn from_bytes_rejects_a_zero_bits_header_instead_of_panicking_on_lookup() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&0u64.to_le_bytes()); // num_bits
+        bytes.extend_from_slice(&1u32.to_le_bytes()); // num_hashes
+        match BloomFilter::from_bytes(&bytes) {
+            Err(SyncError::CorruptData(message)) => assert!(message.contains("nonzero")),
+            other => panic!("expected Err(SyncError::CorruptData(_)), got {other:?}"),
+        }
+    }
+}
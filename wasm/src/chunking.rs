@@ -0,0 +1,326 @@
+//! Content-defined chunking (FastCDC) for delta sync.
+//!
+//! Fixed-offset chunking (e.g. every 4 KiB) means a single byte inserted
+//! near the start of a file shifts every chunk boundary after it, so a
+//! sync engine ends up re-uploading the whole file for a one-line edit.
+//! FastCDC instead picks boundaries based on a rolling hash of the
+//! content itself, so insertions and deletions only disturb the chunks
+//! immediately around them.
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::SyncError;
+use crate::hashing::content_hash;
+use crate::store::ChunkStore;
+
+/// Gear table: 256 pseudo-random 64-bit constants used to mix each byte
+/// into the rolling hash. Fixed and versioned with the crate, since
+/// changing it would reshuffle every chunk boundary already synced.
+#[rustfmt::skip]
+const GEAR: [u64; 256] = [
+    0xcde6509ad0ed0570, 0x8954c266324997e3, 0xd2c126735affb27b, 0x6fdb773aa781b44e,
+    0x4b53bd9fa4e690ae, 0x7638564fd98f0c53, 0xac7f263974ec5224, 0x9b4200d01319b423,
+    0xcfc860d6fce473b8, 0x94bcced5bf287c46, 0xc2593c272370128d, 0x63b1efdfb647b547,
+    0x2a13fa13e5ec44ba, 0xd04d3844eaf1f0ab, 0x7412b48ae5fdfaeb, 0x45863078b92bc4bf,
+    0xd2f4ee706fb95189, 0x434f732c0fb7af60, 0xfccff941c2326be5, 0x5f5fb874ebc40dce,
+    0xca7ebbade58f86e2, 0xbf3c71108fdc711c, 0xeeb24b1812dcc124, 0x6c1119818ba9309c,
+    0x4c10dec28a604581, 0xe0cf6c9372626d56, 0xf9ec2c5d9af26302, 0xea1f058dab9fbf4f,
+    0x9d6b0cb16d14bd3d, 0x09b6852dd865c7be, 0x1b65ecd8d4f6ee33, 0x39a251bdfb6df157,
+    0x77a07159448e2669, 0x17db3508e77cd5a6, 0x008c4a178572b8d6, 0xd8e884622aa3a957,
+    0x9c285ab57ad938f9, 0x5bfbc4a0e7ef9a7e, 0x55d977f4b2b69304, 0xb22ed598ef725f51,
+    0x32df1d2c0ea42baa, 0xedb35187acf345c0, 0xd4835e287f880673, 0xdd0a1ec2af8685f7,
+    0x1cf19c30bbb86b0c, 0xed43759fd0c80ce7, 0x9d3a20acc3672efc, 0xd32a1e45fe1d6b18,
+    0xc58222480c483998, 0xd10e60dd683a7ae7, 0xab7aa2c2a9a19725, 0x25bb388fcc3321a7,
+    0x6afffd0d8ec86445, 0x64bb163b4eb3f76e, 0x6d69f19eb55cc06a, 0xc554573f9308fbdd,
+    0x6ddb81a57d624b29, 0x95359d68cc27be3c, 0xb53725e907086c9a, 0x8b5c5497f3c33c92,
+    0x79a4abb66e81a6be, 0x54acab893035e666, 0x9bb839325dd239e1, 0x86541f4b45680204,
+    0xf40d440c99bf3a98, 0x9ef3ba900b7a0372, 0x0bcbb07e38d38fda, 0xfd599b4b0fcbb6e4,
+    0xa6b3d76facdfb8e4, 0xb6ebb27e588a1a13, 0xd82ba1632c2b9fbd, 0x8f1541e6105e7384,
+    0x5c04244f99355051, 0xebc9856be90a4989, 0xea05a1a6e08f69e7, 0x9edd21af020f24b4,
+    0xb364bd4afb385fa8, 0x390b20ccf267d711, 0xae2d47073cb5e68a, 0x669e6250c272e41c,
+    0x055cbfcd115ef669, 0xa8af10f3bc1f6b25, 0x346be7c56516fdf7, 0xc25c8558cec4cecc,
+    0x50cae2926657fd84, 0x493eecc8d88cade3, 0x81bba3ac193b8142, 0x4603e3eca910b284,
+    0x26d9892334ade3fb, 0xf0a7e1eaf7dcbd96, 0x282ef04d6479e016, 0x93606401ffed2db1,
+    0x1b6d1836988cd09b, 0x807efede74c56af1, 0x28b1ae8ba497104c, 0xd0b15a377769a51e,
+    0xa093c7eb21f44172, 0x2f62463391bfe36f, 0xaeb85f97d71d897f, 0xb7af89d6b459f5e1,
+    0x70af611f92a27e3f, 0xdaf97fb942e58d6e, 0xafeb718079e6d607, 0x2f9d4296b5d70455,
+    0x704c353c8d05f106, 0xaae40d0b7e59bc09, 0xefe82f69eea41464, 0xe1f577df2eaa351a,
+    0xfffa8b627b6891a1, 0x5c244e83cb3da229, 0x6f8d892020e9a30f, 0x54b0dfa8632e459a,
+    0x6bff907193a590e8, 0x2cb2d958bdf2ac4c, 0x4b380c0d16ff9e65, 0x741543338f8c9f2e,
+    0xb74f37014584d6ba, 0x2d66c3a71ad7f1d4, 0xf59337de37c4bd32, 0xa6fe7f521d64ccca,
+    0xc513e904a19c3788, 0xf17fb00d10debf9a, 0x00c769963ac4f2ff, 0xb345029ccd233f3f,
+    0x9fd508c6318f893a, 0xa8dd31b097260629, 0x176591d8e2ee812e, 0x4b2100333aa767c0,
+    0xa8db306af9529e0c, 0xc45663d637f56190, 0x8c402a94652d39b3, 0x1dc84beb8893b35b,
+    0x8d37dd92666d027f, 0xcd8ad3e12d9631dd, 0x52eaad978e697de6, 0xf40c38a260fe4fdb,
+    0xede2ef0cf07f7ae6, 0xdca0b7f595163e4e, 0x4b0ea000e3a9661a, 0xeecd75f39c27f25c,
+    0x7c51aa0f4c6501be, 0x58420ccc2158bf3b, 0xdce64c1e588d9ea8, 0x0fc181c06265f5da,
+    0xce7e53d37cbc338a, 0x988c5ef8053c142f, 0x9b29b24a23fce2a4, 0x84abe226a44260af,
+    0x27d1038438138675, 0x3b33ef963aaa40a4, 0xfe9e2ecd5e7d93c3, 0x32d09934feee13ef,
+    0x26fa8460d8805e34, 0x4a23244f78dc9772, 0x324490238a03b2d2, 0x496b8c2c376a14e9,
+    0x3e38b50c1d895747, 0x5811ea6d862a039a, 0x64501218903940fb, 0x497c781b8bcad2fb,
+    0x47594fdd10c24e6d, 0x14e5e01c68b3fb15, 0x485c1c4407cae37d, 0xea527389913fdf88,
+    0xf4fa623cef09df93, 0x38ab974d01981075, 0xd3b58739fad294b0, 0x7b0940ac4ce8206d,
+    0x3edcf66fd0b4475c, 0x0f01391759f3d49c, 0x1118d7e421fcaf84, 0xe5365d6931ef5bbd,
+    0x0cce5f9ac528262a, 0x98e16df220705a16, 0xdfc5942a28ab2743, 0xe8908ad8f253d636,
+    0xdf71351522f3ab9b, 0xffac5ab2d56915c7, 0xa7f864576295a192, 0x9162242a72c00ed9,
+    0x218c9522cfb9240d, 0x3ff11e050cb376b8, 0x732707fdcb94048e, 0x9f4da88863330c1a,
+    0x63e1c16823351fe8, 0x12c76f406bc61008, 0x9e341a4e3331ea22, 0x50f9e9a1a9c382ad,
+    0xc56544206bacc47e, 0x294a951e29c4773e, 0xe4223ac0e573b5a3, 0x748c7d3a9f53def4,
+    0xa9b3f3a3c95c4eaa, 0xf8fa12cb53a94efc, 0xdc10325d7f45d453, 0x9a43acfa50ef7f97,
+    0x5953edad047f440b, 0xbbbae0f816e70f1a, 0xa76c0736a3576bac, 0x87436338a2c0b8a8,
+    0xd5bffa9113c50c2a, 0xc98b74327aa37196, 0xbd53d5b1e5a4241f, 0xa29d4e13d55cb6f0,
+    0x5b9f9c79a44c44d7, 0xea4394b52b4a06bb, 0xb7c5cb5405da6e79, 0x993e7827fd5e0618,
+    0x09add64d3c5edca8, 0xeac7cdac547a2570, 0x91695e62c0c856f3, 0x8c907faa07536f24,
+    0xb4f15f0f57935689, 0xa6bdfc55a4503881, 0xe4b1de64da11457f, 0x2f105d61ad88b38a,
+    0x9ca6960f05c6e791, 0xdd5c33280539dc1d, 0xc02b409abbb33596, 0x865822018256fb9f,
+    0x8ca38d9a42d6a607, 0xe4fca7f175a18315, 0x0947aa0665f6c29f, 0xa3346bc308d4273f,
+    0x4d95b453371a4f93, 0x0ce39a9db71f8d51, 0xc6378ef5628b18b1, 0x22f96435badf66f1,
+    0xd3bd78f3fce7399d, 0xac8b622e8d178034, 0xb1285b56222d25b2, 0xd3f63f8db98d82d1,
+    0x50432910fdcc5cbd, 0xa933d3a8591e6720, 0xe8e2d664a03d0b30, 0x5dedce34a59ad2ac,
+    0xd0bb53f6b67627bf, 0xb2ec78a3975aaf66, 0x0f00c86752f0afc2, 0x265c339fd1e6ed7a,
+    0x3796f8e9b58d4b97, 0x11158594c9106e97, 0x450de041afd7020f, 0x41ff14c4f8432c12,
+    0xb9babec7320e8e08, 0x62f1c9d5692d20ec, 0xbc295b7aad54f50a, 0x54652ceac674da1f,
+    0x98e57587b905332b, 0xfba03e858896b746, 0xc41315f6a5d83fe2, 0x408bbc967beb06eb,
+    0x45f88bdf5bb49b21, 0x380da646f2e7b124, 0x182de230e460892f, 0xdd4a5292e81d80c5,
+];
+
+fn mask_with_bits(bits: u32) -> u64 {
+    if bits == 0 {
+        0
+    } else {
+        (1u64 << bits) - 1
+    }
+}
+
+/// Find the length of the next chunk at the start of `data`, using the
+/// FastCDC normalized chunking scheme: a stricter mask below `avg` (to
+/// discourage cutting too early) and a looser mask above it (to cap how
+/// large a chunk can grow before we force a cut at `max`).
+fn next_chunk_len(data: &[u8], min: usize, avg: usize, max: usize) -> usize {
+    let max = max.min(data.len());
+    if data.len() <= min {
+        return data.len();
+    }
+
+    let bits = (avg.max(2) as f64).log2().round() as u32;
+    let mask_small = mask_with_bits(bits + 1);
+    let mask_large = mask_with_bits(bits.saturating_sub(1));
+    let normal_size = avg.min(max);
+
+    let mut hash: u64 = 0;
+    let mut i = min;
+    while i < normal_size {
+        hash = (hash << 1).wrapping_add(GEAR[data[i] as usize]);
+        if hash & mask_small == 0 {
+            return i + 1;
+        }
+        i += 1;
+    }
+    while i < max {
+        hash = (hash << 1).wrapping_add(GEAR[data[i] as usize]);
+        if hash & mask_large == 0 {
+            return i + 1;
+        }
+        i += 1;
+    }
+    max
+}
+
+/// One content-defined chunk of a larger buffer.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    offset: u32,
+    length: u32,
+    hash: String,
+}
+
+#[wasm_bindgen]
+impl Chunk {
+    #[wasm_bindgen(getter)]
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn hash(&self) -> String {
+        self.hash.clone()
+    }
+}
+
+/// Split `data` into content-defined chunks whose average size tends
+/// toward `avg`, never smaller than `min` (except for a final short tail
+/// or input shorter than `min`) and never larger than `max`.
+///
+/// Empty input returns no chunks. Input no longer than `min` returns a
+/// single chunk covering the whole buffer.
+///
+/// Returns [`SyncError::InvalidArgument`] unless `min <= avg <= max` and
+/// `max > 0` — in particular, a `max` of `0` would make every chunk
+/// length `0`, so `chunk_content`'s scan would never advance and hang
+/// the WASM instance forever on non-empty input instead of returning.
+#[wasm_bindgen]
+pub fn chunk_content(data: &[u8], min: usize, avg: usize, max: usize) -> Result<Vec<Chunk>, SyncError> {
+    if max == 0 || min > avg || avg > max {
+        return Err(SyncError::InvalidArgument(format!(
+            "invalid chunk bounds: expected min <= avg <= max with max > 0, got min={min} avg={avg} max={max}"
+        )));
+    }
+
+    if data.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut chunks = Vec::new();
+    let mut offset = 0usize;
+    while offset < data.len() {
+        let len = next_chunk_len(&data[offset..], min, avg, max);
+        chunks.push(Chunk {
+            offset: offset as u32,
+            length: len as u32,
+            hash: content_hash(&data[offset..offset + len]),
+        });
+        offset += len;
+    }
+    Ok(chunks)
+}
+
+/// Reconstruct a file from `chunk_hashes` (in file order) by looking each
+/// one up in `store` and concatenating the bytes, so a client only has to
+/// download the chunks it's actually missing instead of the whole file.
+///
+/// An empty `chunk_hashes` reassembles to empty bytes. If any hash isn't
+/// present in `store`, returns [`SyncError::MissingChunk`] naming the
+/// offending hash instead of silently producing a truncated file.
+#[wasm_bindgen]
+pub fn assemble(chunk_hashes: Vec<String>, store: &ChunkStore) -> Result<Vec<u8>, SyncError> {
+    let mut data = Vec::new();
+    for hash in &chunk_hashes {
+        let chunk = store
+            .get(hash)
+            .ok_or_else(|| SyncError::MissingChunk(format!("chunk {hash} not found in the store")))?;
+        data.extend_from_slice(&chunk);
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn pseudo_random_bytes(len: usize) -> Vec<u8> {
+        let mut state: u64 = 0x1234_5678_9abc_def0;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state & 0xff) as u8
+            })
+            .collect()
+    }
+
+    #[wasm_bindgen_test]
+    fn empty_input_returns_no_chunks() {
+        assert!(chunk_content(&[], 64, 256, 1024).unwrap().is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn input_smaller_than_min_is_a_single_chunk() {
+        let data = pseudo_random_bytes(10);
+        let chunks = chunk_content(&data, 64, 256, 1024).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].offset(), 0);
+        assert_eq!(chunks[0].length(), 10);
+    }
+
+    #[wasm_bindgen_test]
+    fn chunks_cover_the_whole_buffer_contiguously() {
+        let data = pseudo_random_bytes(20_000);
+        let chunks = chunk_content(&data, 256, 1024, 4096).unwrap();
+        assert!(chunks.len() > 1);
+
+        let mut expected_offset = 0u32;
+        for chunk in &chunks {
+            assert_eq!(chunk.offset(), expected_offset);
+            expected_offset += chunk.length();
+        }
+        assert_eq!(expected_offset as usize, data.len());
+    }
+
+    #[wasm_bindgen_test]
+    fn insertion_at_the_start_leaves_most_downstream_chunks_unchanged() {
+        let original = pseudo_random_bytes(20_000);
+        let mut edited = pseudo_random_bytes(37);
+        edited.extend_from_slice(&original);
+
+        let before: std::collections::HashSet<_> = chunk_content(&original, 256, 1024, 4096)
+            .unwrap()
+            .iter()
+            .map(|chunk| chunk.hash())
+            .collect();
+        let after = chunk_content(&edited, 256, 1024, 4096).unwrap();
+
+        let unchanged = after.iter().filter(|chunk| before.contains(&chunk.hash())).count();
+        assert!(
+            unchanged * 2 >= after.len(),
+            "expected most chunks to survive a small insertion, kept {unchanged}/{}",
+            after.len()
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn an_empty_chunk_list_assembles_to_empty_bytes() {
+        let store = ChunkStore::new();
+        assert_eq!(assemble(Vec::new(), &store).unwrap(), Vec::<u8>::new());
+    }
+
+    #[wasm_bindgen_test]
+    fn chunking_storing_and_reassembling_reproduces_the_original_file() {
+        let data = pseudo_random_bytes(20_000);
+        let chunks = chunk_content(&data, 256, 1024, 4096).unwrap();
+
+        let mut store = ChunkStore::new();
+        for chunk in &chunks {
+            let bytes = &data[chunk.offset() as usize..(chunk.offset() + chunk.length()) as usize];
+            store.insert(&chunk.hash(), bytes);
+        }
+
+        let chunk_hashes: Vec<String> = chunks.iter().map(|chunk| chunk.hash()).collect();
+        let assembled = assemble(chunk_hashes, &store).unwrap();
+
+        assert_eq!(assembled, data);
+        assert_eq!(content_hash(&assembled), content_hash(&data));
+    }
+
+    #[wasm_bindgen_test]
+    fn a_missing_chunk_is_reported_by_hash() {
+        let store = ChunkStore::new();
+        let err = assemble(vec!["deadbeef".to_string()], &store).unwrap_err();
+        match err {
+            SyncError::MissingChunk(message) => assert!(message.contains("deadbeef")),
+            other => panic!("expected Err(SyncError::MissingChunk(_)), got {other:?}"),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn a_max_of_zero_is_rejected_instead_of_hanging() {
+        let err = chunk_content(&pseudo_random_bytes(10), 0, 0, 0).unwrap_err();
+        match err {
+            SyncError::InvalidArgument(message) => assert!(message.contains("max")),
+            other => panic!("expected Err(SyncError::InvalidArgument(_)), got {other:?}"),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn bounds_out_of_min_avg_max_order_are_rejected() {
+        assert!(chunk_content(&pseudo_random_bytes(10), 1024, 256, 4096).is_err());
+        assert!(chunk_content(&pseudo_random_bytes(10), 64, 4096, 1024).is_err());
+    }
+}
@@ -1,5 +1,11 @@
 use wasm_bindgen::prelude::*;
 
+pub mod content_hash;
+pub mod delta;
+pub mod manifest;
+pub mod parallel_index;
+pub mod vault_adapter;
+
 // This is called when the wasm module is instantiated
 // Skip during tests to avoid entry point conflicts with test harness
 #[cfg(not(test))]
@@ -20,7 +26,9 @@ pub fn greet(name: &str) -> String {
 
 /// Example: Calculate a hash or perform heavy computation
 /// This demonstrates why Rust + WASM is beneficial for performance
+#[deprecated(note = "not collision-resistant; use content_hash::content_id instead")]
 #[wasm_bindgen]
+#[allow(deprecated)]
 pub fn compute_hash(input: &str) -> u64 {
     // Simple hash function (not cryptographic - just for demo)
     let mut hash: u64 = 0;
@@ -41,6 +49,7 @@ mod tests {
     }
 
     #[wasm_bindgen_test]
+    #[allow(deprecated)]
     fn test_compute_hash() {
         let hash1 = compute_hash("test");
         let hash2 = compute_hash("test");
@@ -0,0 +1,135 @@
+//! A Lamport logical clock, for ordering edits across devices whose wall
+//! clocks can't be trusted to agree.
+//!
+//! Wall-clock timestamps are still useful for humans and for tombstone
+//! expiry (see [`crate::tombstone`]), but they can go backwards across a
+//! clock skew or DST change, which silently corrupts last-writer-wins
+//! tie-breaking. A Lamport clock only ever moves forward.
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::SyncError;
+
+/// A Lamport clock: a single counter that only ever increases.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LamportClock {
+    value: u64,
+}
+
+#[wasm_bindgen]
+impl LamportClock {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> LamportClock {
+        LamportClock::default()
+    }
+
+    /// The current value, without advancing the clock.
+    #[wasm_bindgen(getter)]
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// Advance the clock for a local event and return the new value.
+    pub fn tick(&mut self) -> u64 {
+        self.value += 1;
+        self.value
+    }
+
+    /// Fold in a `remote` clock value observed from another device: the
+    /// clock jumps to whichever is later plus one, so the next `tick`
+    /// on either side is guaranteed to come after both.
+    pub fn observe(&mut self, remote: u64) {
+        self.value = self.value.max(remote) + 1;
+    }
+
+    /// Serialize the current value to JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self.value).expect("u64 serialization is infallible")
+    }
+
+    /// Parse a value previously produced by [`LamportClock::to_json`].
+    pub fn from_json(s: &str) -> Result<LamportClock, SyncError> {
+        let value: u64 =
+            serde_json::from_str(s).map_err(|err| SyncError::CorruptData(format!("invalid clock JSON: {err}")))?;
+        Ok(LamportClock { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn tick_returns_strictly_increasing_values() {
+        let mut clock = LamportClock::new();
+        let a = clock.tick();
+        let b = clock.tick();
+        let c = clock.tick();
+        assert!(a < b);
+        assert!(b < c);
+    }
+
+    #[wasm_bindgen_test]
+    fn observe_then_tick_stays_ahead_of_the_observed_remote_value() {
+        let mut clock = LamportClock::new();
+        clock.tick();
+        clock.tick();
+
+        clock.observe(10);
+        assert_eq!(clock.value(), 11);
+
+        let next = clock.tick();
+        assert_eq!(next, 12);
+        assert!(next > 11);
+    }
+
+    #[wasm_bindgen_test]
+    fn observing_a_smaller_remote_value_still_advances() {
+        let mut clock = LamportClock::new();
+        clock.tick();
+        clock.tick();
+        clock.tick();
+
+        clock.observe(1);
+        assert_eq!(clock.value(), 4);
+    }
+
+    #[wasm_bindgen_test]
+    fn two_interleaved_clocks_converge_through_message_passing() {
+        let mut a = LamportClock::new();
+        let mut b = LamportClock::new();
+
+        let t1 = a.tick();
+        b.observe(t1);
+        let t2 = b.tick();
+        a.observe(t2);
+        let t3 = a.tick();
+
+        assert!(t1 < t2);
+        assert!(t2 < t3);
+        assert_eq!(a.value(), t3);
+        assert!(b.value() <= a.value());
+    }
+
+    #[wasm_bindgen_test]
+    fn from_json_round_trips_to_json() {
+        let mut clock = LamportClock::new();
+        clock.tick();
+        clock.tick();
+
+        let json = clock.to_json();
+        let reparsed = LamportClock::from_json(&json).unwrap();
+        assert_eq!(reparsed.value(), clock.value());
+    }
+
+    #[wasm_bindgen_test]
+    fn from_json_rejects_malformed_input_with_a_clear_error() {
+        let err = LamportClock::from_json("not json").unwrap_err();
+        match err {
+            SyncError::CorruptData(message) => assert!(message.contains("invalid clock JSON")),
+            other => panic!("expected SyncError::CorruptData, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! `NoteMeta`/`Manifest` and their MessagePack encode/decode functions.
+//!
+//! `encode_manifest`/`decode_manifest` convert between a `Manifest` and a
+//! `Vec<u8>` via `serde_wasm_bindgen` + `rmp_serde`, so JS can hand over an
+//! entire vault's metadata as one buffer rather than one call per field per
+//! entry.
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+/// Metadata for a single vault entry (note or attachment).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoteMeta {
+    pub path: String,
+    pub content_id: String,
+    pub size: u64,
+    pub mtime: u64,
+    pub tags: Vec<String>,
+}
+
+/// A full vault manifest: one [`NoteMeta`] per entry.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub entries: Vec<NoteMeta>,
+}
+
+/// Encode a manifest (a bare array of note-metadata objects, passed from
+/// JS) into a compact MessagePack byte buffer.
+#[wasm_bindgen]
+pub fn encode_manifest(entries: JsValue) -> Result<Vec<u8>, JsValue> {
+    let entries: Vec<NoteMeta> = serde_wasm_bindgen::from_value(entries)?;
+    rmp_serde::to_vec(&Manifest { entries }).map_err(|err| JsValue::from_str(&err.to_string()))
+}
+
+/// Decode a MessagePack-encoded manifest back into a bare JS array of
+/// note-metadata objects.
+#[wasm_bindgen]
+pub fn decode_manifest(bytes: &[u8]) -> Result<JsValue, JsValue> {
+    let manifest: Manifest =
+        rmp_serde::from_slice(bytes).map_err(|err| JsValue::from_str(&err.to_string()))?;
+    serde_wasm_bindgen::to_value(&manifest.entries).map_err(JsValue::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn manifest_roundtrips_through_messagepack() {
+        let manifest = Manifest {
+            entries: vec![NoteMeta {
+                path: "notes/todo.md".to_string(),
+                content_id: "abc123".to_string(),
+                size: 42,
+                mtime: 1_700_000_000,
+                tags: vec!["work".to_string(), "urgent".to_string()],
+            }],
+        };
+
+        let bytes = rmp_serde::to_vec(&manifest).unwrap();
+        let decoded: Manifest = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(decoded, manifest);
+    }
+
+    #[wasm_bindgen_test]
+    fn encode_decode_manifest_roundtrips_through_a_bare_js_array() {
+        let entries = vec![NoteMeta {
+            path: "notes/todo.md".to_string(),
+            content_id: "abc123".to_string(),
+            size: 42,
+            mtime: 1_700_000_000,
+            tags: vec!["work".to_string(), "urgent".to_string()],
+        }];
+
+        let js_entries = serde_wasm_bindgen::to_value(&entries).unwrap();
+        let bytes = encode_manifest(js_entries).unwrap();
+
+        let decoded_js = decode_manifest(&bytes).unwrap();
+        let decoded: Vec<NoteMeta> = serde_wasm_bindgen::from_value(decoded_js).unwrap();
+        assert_eq!(decoded, entries);
+    }
+}
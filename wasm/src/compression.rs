@@ -0,0 +1,68 @@
+//! Standalone deflate compression, for compressing manifests and deltas
+//! independently of [`crate::pipeline`]'s combined compress-then-encrypt
+//! flow.
+
+use std::io::{Read, Write};
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use wasm_bindgen::prelude::*;
+
+use crate::error::SyncError;
+
+/// Deflate-compress `data`. `level` is clamped to the valid 0 (no
+/// compression) – 9 (best compression) range rather than erroring on an
+/// out-of-range value.
+#[wasm_bindgen]
+pub fn compress(data: &[u8], level: u8) -> Vec<u8> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::new(level.min(9) as u32));
+    encoder.write_all(data).expect("writing to a Vec<u8> is infallible");
+    encoder.finish().expect("finishing a Vec<u8> encoder is infallible")
+}
+
+/// Inflate data produced by [`compress`]. Corrupt input returns an error
+/// rather than panicking or hanging.
+#[wasm_bindgen]
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>, SyncError> {
+    crate::guard(|| {
+        let mut out = Vec::new();
+        DeflateDecoder::new(data)
+            .read_to_end(&mut out)
+            .map_err(|err| SyncError::CorruptData(format!("decompression failed: {err}")))?;
+        Ok(out)
+    })?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn round_trips_at_every_clamped_level() {
+        let data = b"the quick brown fox jumps over the lazy dog";
+        for level in [0u8, 5, 9, 200, 255] {
+            let compressed = compress(data, level);
+            assert_eq!(decompress(&compressed).unwrap(), data);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn highly_repetitive_input_shrinks_by_a_large_factor() {
+        let data = "a".repeat(100_000);
+        let compressed = compress(data.as_bytes(), 9);
+        assert!(
+            compressed.len() * 50 < data.len(),
+            "expected a large shrink, got {} from {}",
+            compressed.len(),
+            data.len()
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn decompressing_corrupt_input_is_an_error_not_a_panic() {
+        let garbage = vec![0xffu8; 64];
+        assert!(decompress(&garbage).is_err());
+    }
+}
@@ -0,0 +1,84 @@
+//! Batch content-hashing of many vault files.
+//!
+//! With the `parallel` feature enabled, this links in `rayon` and
+//! `wasm-bindgen-rayon`, which backs rayon's thread pool with a pool of Web
+//! Workers sharing wasm linear memory. That pool has to be spun up on the
+//! JS side first — call the exported `init_thread_pool(navigator.hardwareConcurrency)`
+//! once and await it before calling [`index_files`] — because it needs
+//! `SharedArrayBuffer` and cross-origin isolation, which not every host the
+//! adapter runs on provides. Builds without the feature just hash each
+//! buffer on the calling thread, which is always safe to ship.
+
+use wasm_bindgen::prelude::*;
+
+use crate::content_hash::content_id;
+
+#[cfg(feature = "parallel")]
+pub use wasm_bindgen_rayon::init_thread_pool;
+
+/// Content ID and size for one hashed file, in the same order the caller
+/// passed the corresponding buffer in.
+#[wasm_bindgen(getter_with_clone)]
+pub struct IndexedFile {
+    pub content_id: String,
+    pub size: u32,
+}
+
+/// Hash a batch of file buffers, returning one [`IndexedFile`] per input
+/// buffer in the original order. With the `parallel` feature the work runs
+/// on rayon's worker-thread pool; without it every buffer is hashed
+/// sequentially on the calling thread. The choice is made at build time,
+/// not by a runtime check for thread support.
+#[wasm_bindgen]
+pub fn index_files(buffers: Vec<js_sys::Uint8Array>) -> Vec<IndexedFile> {
+    let files: Vec<Vec<u8>> = buffers.iter().map(|buffer| buffer.to_vec()).collect();
+    index_slices(&files)
+}
+
+fn index_slices(files: &[Vec<u8>]) -> Vec<IndexedFile> {
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        files
+            .par_iter()
+            .map(|bytes| IndexedFile {
+                content_id: content_id(bytes),
+                size: bytes.len() as u32,
+            })
+            .collect()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    {
+        files
+            .iter()
+            .map(|bytes| IndexedFile {
+                content_id: content_id(bytes),
+                size: bytes.len() as u32,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn indexes_files_in_order() {
+        let files = vec![b"one".to_vec(), b"two".to_vec(), b"three".to_vec()];
+        let indexed = index_slices(&files);
+
+        assert_eq!(indexed.len(), 3);
+        assert_eq!(indexed[0].content_id, content_id(b"one"));
+        assert_eq!(indexed[0].size, 3);
+        assert_eq!(indexed[1].content_id, content_id(b"two"));
+        assert_eq!(indexed[2].content_id, content_id(b"three"));
+    }
+
+    #[wasm_bindgen_test]
+    fn handles_empty_batch() {
+        assert!(index_slices(&[]).is_empty());
+    }
+}
@@ -0,0 +1,116 @@
+//! Timestamp-plus-hash conflict detection between a local and remote copy
+//! of a file, relative to their last-synced common base.
+//!
+//! Comparing hashes against the shared base (rather than just comparing
+//! local and remote to each other) is what lets a change on only one
+//! side win outright: if the other side's hash still matches the base,
+//! it never touched the file, so there's nothing to arbitrate.
+
+use wasm_bindgen::prelude::*;
+
+/// A file's hash and mtime at some point in time, as recorded by one side
+/// (or the last-synced base) for [`classify_change`].
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileState {
+    hash: String,
+    mtime: f64,
+}
+
+#[wasm_bindgen]
+impl FileState {
+    #[wasm_bindgen(constructor)]
+    pub fn new(hash: &str, mtime: f64) -> FileState {
+        FileState {
+            hash: hash.to_string(),
+            mtime,
+        }
+    }
+}
+
+/// How a local and remote copy have diverged from their shared base.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeClass {
+    /// Neither side's hash differs from the base.
+    NoChange,
+    /// Only the local hash differs from the base.
+    LocalWins,
+    /// Only the remote hash differs from the base.
+    RemoteWins,
+    /// Both sides' hashes differ from the base, and from each other.
+    Conflict,
+}
+
+/// Classify how `local` and `remote` relate to their shared `base`.
+///
+/// If only one side's hash changed from `base`, that side wins outright
+/// — mtimes never come into it, since there's nothing to arbitrate. If
+/// both sides changed but ended up with the same hash (e.g. the same
+/// edit made independently on both devices), it's [`ChangeClass::NoChange`]:
+/// there's nothing left to reconcile. Only both sides changing to
+/// *different* hashes is a [`ChangeClass::Conflict`], regardless of which
+/// mtime is newer.
+#[wasm_bindgen]
+pub fn classify_change(base: &FileState, local: &FileState, remote: &FileState) -> ChangeClass {
+    let local_changed = local.hash != base.hash;
+    let remote_changed = remote.hash != base.hash;
+
+    match (local_changed, remote_changed) {
+        (false, false) => ChangeClass::NoChange,
+        (true, false) => ChangeClass::LocalWins,
+        (false, true) => ChangeClass::RemoteWins,
+        (true, true) if local.hash == remote.hash => ChangeClass::NoChange,
+        (true, true) => ChangeClass::Conflict,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn state(hash: &str, mtime: f64) -> FileState {
+        FileState::new(hash, mtime)
+    }
+
+    #[wasm_bindgen_test]
+    fn neither_side_changed_is_no_change() {
+        let base = state("h1", 100.0);
+        let local = state("h1", 100.0);
+        let remote = state("h1", 100.0);
+        assert_eq!(classify_change(&base, &local, &remote), ChangeClass::NoChange);
+    }
+
+    #[wasm_bindgen_test]
+    fn only_local_changed_wins_regardless_of_mtime() {
+        let base = state("h1", 100.0);
+        let local = state("h2", 50.0);
+        let remote = state("h1", 999.0);
+        assert_eq!(classify_change(&base, &local, &remote), ChangeClass::LocalWins);
+    }
+
+    #[wasm_bindgen_test]
+    fn only_remote_changed_wins_regardless_of_mtime() {
+        let base = state("h1", 100.0);
+        let local = state("h1", 999.0);
+        let remote = state("h2", 50.0);
+        assert_eq!(classify_change(&base, &local, &remote), ChangeClass::RemoteWins);
+    }
+
+    #[wasm_bindgen_test]
+    fn both_sides_changed_to_different_hashes_is_a_conflict_regardless_of_mtime() {
+        let base = state("h1", 100.0);
+        let local = state("h2", 50.0);
+        let remote = state("h3", 999.0);
+        assert_eq!(classify_change(&base, &local, &remote), ChangeClass::Conflict);
+    }
+
+    #[wasm_bindgen_test]
+    fn both_sides_making_the_same_edit_is_no_change_not_a_conflict() {
+        let base = state("h1", 100.0);
+        let local = state("h2", 50.0);
+        let remote = state("h2", 999.0);
+        assert_eq!(classify_change(&base, &local, &remote), ChangeClass::NoChange);
+    }
+}
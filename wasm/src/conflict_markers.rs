@@ -0,0 +1,117 @@
+//! Git-style conflict marker rendering, built on top of [`crate::merge`].
+//!
+//! When [`crate::merge::merge3`] can't auto-resolve a region, this module
+//! turns its [`crate::merge::Conflict`] list into familiar
+//! `<<<<<<<`/`=======`/`>>>>>>>` markers so the user can resolve them
+//! directly in the Obsidian editor, plus a companion function to strip
+//! them back out.
+
+use wasm_bindgen::prelude::*;
+
+use crate::diff::split_lines;
+use crate::merge::MergeResult;
+
+fn lines_of(text: &str) -> Vec<String> {
+    if text.is_empty() {
+        Vec::new()
+    } else {
+        text.split('\n').map(str::to_string).collect()
+    }
+}
+
+/// Render `result` as text with git-style conflict markers, labeling each
+/// side with `local_label`/`remote_label`. Conflicts are inserted in
+/// order with a running offset, so adjacent or back-to-back conflicts
+/// each get their own well-formed block.
+#[wasm_bindgen]
+pub fn render_conflict_markers(result: &MergeResult, local_label: &str, remote_label: &str) -> String {
+    let mut lines: Vec<String> = split_lines(&result.merged_text())
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+
+    let mut offset: i64 = 0;
+    for conflict in result.conflicts() {
+        let insert_at = (conflict.start() as i64 + offset) as usize;
+
+        let mut block = vec![format!("<<<<<<< {local_label}")];
+        block.extend(lines_of(&conflict.local_text()));
+        block.push("=======".to_string());
+        block.extend(lines_of(&conflict.remote_text()));
+        block.push(format!(">>>>>>> {remote_label}"));
+
+        offset += block.len() as i64;
+        lines.splice(insert_at..insert_at, block);
+    }
+
+    lines.join("\n")
+}
+
+/// Strip conflict markers produced by [`render_conflict_markers`], always
+/// keeping the local ("ours") side of each conflict.
+#[wasm_bindgen]
+pub fn strip_conflict_markers(text: &str) -> String {
+    let mut out = Vec::new();
+    let mut in_conflict = false;
+    let mut on_remote_side = false;
+
+    for line in split_lines(text) {
+        if line.starts_with("<<<<<<<") {
+            in_conflict = true;
+            on_remote_side = false;
+            continue;
+        }
+        if in_conflict && line == "=======" {
+            on_remote_side = true;
+            continue;
+        }
+        if in_conflict && line.starts_with(">>>>>>>") {
+            in_conflict = false;
+            continue;
+        }
+        if !in_conflict || !on_remote_side {
+            out.push(line);
+        }
+    }
+
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merge::merge3;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn markers_carry_both_sides_labeled() {
+        let result = merge3("a\nb\nc", "a\nLOCAL\nc", "a\nREMOTE\nc");
+        let rendered = render_conflict_markers(&result, "phone", "laptop");
+        assert_eq!(
+            rendered,
+            "a\n<<<<<<< phone\nLOCAL\n=======\nREMOTE\n>>>>>>> laptop\nc"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn strip_recovers_the_local_side() {
+        let local = "a\nLOCAL\nc";
+        let result = merge3("a\nb\nc", local, "a\nREMOTE\nc");
+        let rendered = render_conflict_markers(&result, "phone", "laptop");
+        assert_eq!(strip_conflict_markers(&rendered), local);
+    }
+
+    #[wasm_bindgen_test]
+    fn adjacent_conflicts_each_get_well_formed_markers() {
+        let base = "a\nb\nc\nd";
+        let local = "A\nb\nC\nd";
+        let remote = "X\nb\nY\nd";
+        let result = merge3(base, local, remote);
+        assert_eq!(result.conflicts().len(), 2);
+
+        let rendered = render_conflict_markers(&result, "L", "R");
+        assert_eq!(rendered.matches("<<<<<<<").count(), 2);
+        assert_eq!(rendered.matches("=======").count(), 2);
+        assert_eq!(rendered.matches(">>>>>>>").count(), 2);
+    }
+}
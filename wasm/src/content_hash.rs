@@ -0,0 +1,86 @@
+//! Content-addressed hashing for vault sync change detection.
+//!
+//! Replaces the old demo hash in `compute_hash` with a real,
+//! collision-resistant digest (BLAKE3) so the sync engine can tell which
+//! notes changed without re-uploading everything.
+//!
+//! [`content_id`] and [`ContentHasher`] are the original names for this;
+//! [`crate::hashing::content_hash`] and [`crate::hashing::Hasher`] are the
+//! same BLAKE3 digest under the names every later module in this crate
+//! actually imports. Both names are kept exported (renaming a
+//! `#[wasm_bindgen]` function is a breaking change for JS callers), but
+//! only one BLAKE3 call path exists: this module delegates to
+//! [`crate::hashing`] instead of hashing a second time.
+
+#![allow(deprecated)]
+
+use wasm_bindgen::prelude::*;
+
+use crate::hashing::{content_hash, Hasher};
+
+/// Compute a stable content ID for `bytes`.
+///
+/// Returns the BLAKE3 digest of `bytes` as a lowercase hex string, suitable
+/// as a dedup/sync index key.
+#[deprecated(note = "use hashing::content_hash instead")]
+#[wasm_bindgen]
+pub fn content_id(bytes: &[u8]) -> String {
+    content_hash(bytes)
+}
+
+/// Incremental BLAKE3 hasher for streaming large notes or attachments in
+/// from JS a chunk at a time instead of buffering the whole file first.
+#[deprecated(note = "use hashing::Hasher instead")]
+#[wasm_bindgen]
+pub struct ContentHasher {
+    inner: Hasher,
+}
+
+#[wasm_bindgen]
+impl ContentHasher {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> ContentHasher {
+        ContentHasher { inner: Hasher::new() }
+    }
+
+    /// Feed the next chunk of bytes into the running hash.
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.inner.update(chunk);
+    }
+
+    /// Consume the hasher and return the final content ID as hex.
+    pub fn finalize(self) -> String {
+        self.inner.finalize()
+    }
+}
+
+impl Default for ContentHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn content_id_is_stable_and_distinguishes_input() {
+        assert_eq!(content_id(b"test"), content_id(b"test"));
+        assert_ne!(content_id(b"test"), content_id(b"different"));
+    }
+
+    #[wasm_bindgen_test]
+    fn content_id_agrees_with_hashing_content_hash() {
+        assert_eq!(content_id(b"test"), content_hash(b"test"));
+    }
+
+    #[wasm_bindgen_test]
+    fn incremental_hash_matches_one_shot() {
+        let mut hasher = ContentHasher::new();
+        hasher.update(b"te");
+        hasher.update(b"st");
+        assert_eq!(hasher.finalize(), content_id(b"test"));
+    }
+}
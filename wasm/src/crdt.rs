@@ -0,0 +1,210 @@
+//! An RGA (Replicated Growable Array) CRDT for text, so two devices
+//! editing the same note live can converge without last-writer-wins
+//! throwing away one side's keystrokes.
+//!
+//! Every character ever inserted gets a globally unique id — its Lamport
+//! `(clock, site_id)` pair — and remembers the id of the character it
+//! was inserted immediately after (its "origin"). That's enough to
+//! recompute a single canonical ordering purely from the set of
+//! characters and their origins, with no dependence on what order the
+//! inserts/deletes/merges actually happened in: two replicas holding the
+//! same set of characters always linearize them the same way. Deletions
+//! only ever flip a tombstone flag — the character itself is kept so it
+//! can still serve as another insert's origin.
+
+use std::collections::BTreeMap;
+
+use wasm_bindgen::prelude::*;
+
+/// A character's globally unique id: its Lamport clock followed by the
+/// site that created it, so ids from different sites never collide and
+/// concurrent inserts at the same position still sort deterministically.
+type ElementId = (u64, u32);
+
+#[derive(Debug, Clone, PartialEq)]
+struct Element {
+    ch: char,
+    /// The id of the character this one was inserted immediately after,
+    /// or `None` if it was inserted at the very start of the text.
+    origin: Option<ElementId>,
+    deleted: bool,
+}
+
+/// A CRDT text sequence supporting concurrent, order-independent edits.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RgaText {
+    elements: BTreeMap<ElementId, Element>,
+}
+
+#[wasm_bindgen]
+impl RgaText {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> RgaText {
+        RgaText::default()
+    }
+
+    /// Insert `ch` at visible position `index`, tagged with the id
+    /// `(clock, site_id)` from the inserting site's [`crate::clock::LamportClock`].
+    /// `index` is clamped to the current length, so an index past the end
+    /// just appends.
+    pub fn insert(&mut self, index: usize, ch: char, site_id: u32, clock: u64) {
+        let visible = self.visible_order();
+        let index = index.min(visible.len());
+        let origin = index.checked_sub(1).map(|i| visible[i]);
+        self.elements.insert((clock, site_id), Element { ch, origin, deleted: false });
+    }
+
+    /// Tombstone the character at visible position `index`, if any.
+    /// `site_id` identifies the deleting site for API symmetry with
+    /// [`RgaText::insert`]; it isn't otherwise needed, since a boolean
+    /// tombstone converges the same way regardless of who set it.
+    pub fn delete(&mut self, index: usize, _site_id: u32) {
+        let visible = self.visible_order();
+        if let Some(&id) = visible.get(index) {
+            if let Some(element) = self.elements.get_mut(&id) {
+                element.deleted = true;
+            }
+        }
+    }
+
+    /// The current visible text, in canonical order.
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.visible_order().into_iter().map(|id| self.elements[&id].ch).collect()
+    }
+
+    /// Merge `self` and `other` into a new, convergent `RgaText`
+    /// containing every character either side has ever seen. Merging is
+    /// commutative and idempotent: `merge(a, b)` and `merge(b, a)`
+    /// always produce identical text, regardless of how many times or
+    /// in what order two replicas' states have already been merged.
+    pub fn merge(&self, other: &RgaText) -> RgaText {
+        let mut elements = self.elements.clone();
+        for (&id, element) in &other.elements {
+            elements
+                .entry(id)
+                .and_modify(|existing| existing.deleted |= element.deleted)
+                .or_insert_with(|| element.clone());
+        }
+        RgaText { elements }
+    }
+
+    /// Every character's id, in canonical order, tombstoned or not.
+    fn full_order(&self) -> Vec<ElementId> {
+        let mut children: BTreeMap<Option<ElementId>, Vec<ElementId>> = BTreeMap::new();
+        for (&id, element) in &self.elements {
+            children.entry(element.origin).or_default().push(id);
+        }
+        // Concurrent siblings (characters inserted right after the same
+        // origin) sort by descending id, so whichever insert has the
+        // higher Lamport clock — or, on a tie, the higher site id — ends
+        // up closest to the shared origin. This is an arbitrary but
+        // total order, which is all convergence requires.
+        for siblings in children.values_mut() {
+            siblings.sort_unstable_by(|a, b| b.cmp(a));
+        }
+
+        fn visit(id: ElementId, children: &BTreeMap<Option<ElementId>, Vec<ElementId>>, out: &mut Vec<ElementId>) {
+            out.push(id);
+            if let Some(kids) = children.get(&Some(id)) {
+                for &kid in kids {
+                    visit(kid, children, out);
+                }
+            }
+        }
+
+        let mut order = Vec::with_capacity(self.elements.len());
+        if let Some(roots) = children.get(&None) {
+            for &root in roots {
+                visit(root, &children, &mut order);
+            }
+        }
+        order
+    }
+
+    fn visible_order(&self) -> Vec<ElementId> {
+        self.full_order().into_iter().filter(|id| !self.elements[id].deleted).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn sequential_local_inserts_build_up_the_string() {
+        let mut text = RgaText::new();
+        text.insert(0, 'a', 1, 1);
+        text.insert(1, 'b', 1, 2);
+        text.insert(2, 'c', 1, 3);
+        assert_eq!(text.to_string(), "abc");
+    }
+
+    #[wasm_bindgen_test]
+    fn delete_removes_a_character_from_the_visible_text() {
+        let mut text = RgaText::new();
+        text.insert(0, 'a', 1, 1);
+        text.insert(1, 'b', 1, 2);
+        text.insert(2, 'c', 1, 3);
+        text.delete(1, 1);
+        assert_eq!(text.to_string(), "ac");
+    }
+
+    #[wasm_bindgen_test]
+    fn concurrent_inserts_at_the_same_position_merge_deterministically_by_id() {
+        let mut a = RgaText::new();
+        a.insert(0, 'A', 1, 1);
+
+        let mut b = RgaText::new();
+        b.insert(0, 'B', 2, 1);
+
+        let ab = a.merge(&b);
+        let ba = b.merge(&a);
+        assert_eq!(ab, ba);
+        // Same Lamport clock (1) on both sides, so the higher site id
+        // (2) sorts first.
+        assert_eq!(ab.to_string(), "BA");
+    }
+
+    #[wasm_bindgen_test]
+    fn concurrent_delete_and_insert_both_survive_the_merge() {
+        let mut base = RgaText::new();
+        base.insert(0, 'a', 1, 1);
+        base.insert(1, 'b', 1, 2);
+        base.insert(2, 'c', 1, 3);
+
+        let mut deleter = base.clone();
+        deleter.delete(1, 1); // removes 'b', unaware of the concurrent insert
+
+        let mut inserter = base.clone();
+        inserter.insert(1, 'X', 2, 10); // inserts between 'a' and 'b', unaware of the concurrent delete
+
+        let merged = deleter.merge(&inserter);
+        assert_eq!(merged.to_string(), "aXc");
+    }
+
+    #[wasm_bindgen_test]
+    fn merge_converges_regardless_of_which_side_it_is_applied_from() {
+        let mut base = RgaText::new();
+        base.insert(0, 'h', 1, 1);
+        base.insert(1, 'i', 1, 2);
+
+        let mut a = base.clone();
+        a.insert(2, '!', 1, 3);
+
+        let mut b = base.clone();
+        b.delete(0, 2);
+        b.insert(1, 'e', 2, 4);
+
+        let merged_ab = a.merge(&b);
+        let merged_ba = b.merge(&a);
+        assert_eq!(merged_ab, merged_ba);
+        assert_eq!(merged_ab.to_string(), merged_ba.to_string());
+
+        // Merging repeatedly (a replica re-receiving a state it already
+        // has) doesn't change the result.
+        assert_eq!(merged_ab.merge(&a).merge(&b), merged_ab);
+    }
+}
@@ -0,0 +1,208 @@
+//! AES-256-GCM encryption at the WASM boundary, so vault content is
+//! encrypted before it ever leaves the device.
+
+use aes_gcm::aead::{Aead, KeyInit, Nonce};
+use aes_gcm::{Aes256Gcm, Key};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use wasm_bindgen::prelude::*;
+
+use crate::error::SyncError;
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const MIN_SALT_LEN: usize = 8;
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn cipher_from_key(key: &[u8]) -> Result<Aes256Gcm, SyncError> {
+    if key.len() != KEY_LEN {
+        return Err(SyncError::InvalidArgument(format!(
+            "AES-256-GCM key must be {KEY_LEN} bytes, got {}",
+            key.len()
+        )));
+    }
+    Ok(Aes256Gcm::new(&Key::<Aes256Gcm>::try_from(key).unwrap()))
+}
+
+fn nonce_from_slice(nonce: &[u8]) -> Result<Nonce<Aes256Gcm>, SyncError> {
+    if nonce.len() != NONCE_LEN {
+        return Err(SyncError::InvalidArgument(format!(
+            "AES-256-GCM nonce must be {NONCE_LEN} bytes, got {}",
+            nonce.len()
+        )));
+    }
+    Ok(Nonce::<Aes256Gcm>::try_from(nonce).unwrap())
+}
+
+/// Encrypt `plaintext` with AES-256-GCM. `key` must be 32 bytes and
+/// `nonce` 12 bytes, or this returns an error instead of panicking.
+#[wasm_bindgen]
+pub fn encrypt(plaintext: &[u8], key: &[u8], nonce: &[u8]) -> Result<Vec<u8>, SyncError> {
+    let cipher = cipher_from_key(key)?;
+    let nonce = nonce_from_slice(nonce)?;
+    cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| SyncError::EncryptFailed("AES-256-GCM encryption failed".to_string()))
+}
+
+/// Decrypt `ciphertext` produced by [`encrypt`] with the same key and
+/// nonce. A tampered ciphertext, wrong key, or wrong nonce fails
+/// authentication and returns an error rather than partial plaintext.
+#[wasm_bindgen]
+pub fn decrypt(ciphertext: &[u8], key: &[u8], nonce: &[u8]) -> Result<Vec<u8>, SyncError> {
+    let cipher = cipher_from_key(key)?;
+    let nonce = nonce_from_slice(nonce)?;
+    cipher.decrypt(&nonce, ciphertext).map_err(|_| {
+        SyncError::DecryptFailed("AES-256-GCM decryption failed: authentication tag mismatch".to_string())
+    })
+}
+
+/// Derive a 32-byte AES-256-GCM key from a user-typed `passphrase` and a
+/// `salt`, via PBKDF2-HMAC-SHA256 with `iterations` rounds.
+///
+/// The same passphrase, salt, and iteration count always produce the
+/// same key, so a re-installed plugin can derive the key needed to
+/// decrypt data synced from an earlier install. `passphrase` must be
+/// non-empty and `salt` at least [`MIN_SALT_LEN`] bytes, or this returns
+/// an error instead of silently deriving a weak key.
+#[wasm_bindgen]
+pub fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<Vec<u8>, SyncError> {
+    if passphrase.is_empty() {
+        return Err(SyncError::InvalidArgument("passphrase must not be empty".to_string()));
+    }
+    if salt.len() < MIN_SALT_LEN {
+        return Err(SyncError::InvalidArgument(format!(
+            "salt must be at least {MIN_SALT_LEN} bytes, got {}",
+            salt.len()
+        )));
+    }
+
+    let mut key = vec![0u8; KEY_LEN];
+    pbkdf2::pbkdf2_hmac::<sha2::Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    Ok(key)
+}
+
+/// Compute a detached HMAC-SHA256 tag over `data` keyed by `key`, for
+/// verifying a blob hasn't been tampered with at rest on an untrusted
+/// remote independently of AES-GCM's own built-in authentication (e.g.
+/// checking an encrypted blob's integrity without decrypting it first).
+#[wasm_bindgen]
+pub fn hmac_sign(data: &[u8], key: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC-SHA256 accepts a key of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Verify a tag produced by [`hmac_sign`]. Comparison happens in constant
+/// time (via the `hmac` crate's own `verify_slice`), so a mismatch takes
+/// the same time regardless of where the first differing byte falls,
+/// instead of leaking that via timing.
+#[wasm_bindgen]
+pub fn hmac_verify(data: &[u8], key: &[u8], tag: &[u8]) -> bool {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC-SHA256 accepts a key of any length");
+    mac.update(data);
+    mac.verify_slice(tag).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    const KEY: [u8; KEY_LEN] = [7u8; KEY_LEN];
+    const NONCE: [u8; NONCE_LEN] = [9u8; NONCE_LEN];
+
+    #[wasm_bindgen_test]
+    fn round_trips_plaintext() {
+        let plaintext = b"the secret contents of a note";
+        let ciphertext = encrypt(plaintext, &KEY, &NONCE).unwrap();
+        assert_eq!(decrypt(&ciphertext, &KEY, &NONCE).unwrap(), plaintext);
+    }
+
+    #[wasm_bindgen_test]
+    fn a_flipped_ciphertext_byte_fails_authentication() {
+        let plaintext = b"the secret contents of a note";
+        let mut ciphertext = encrypt(plaintext, &KEY, &NONCE).unwrap();
+        ciphertext[0] ^= 0x01;
+        assert!(decrypt(&ciphertext, &KEY, &NONCE).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn a_failed_decrypt_crosses_the_boundary_as_decrypt_failed() {
+        let plaintext = b"the secret contents of a note";
+        let mut ciphertext = encrypt(plaintext, &KEY, &NONCE).unwrap();
+        ciphertext[0] ^= 0x01;
+
+        let js_value: JsValue = decrypt(&ciphertext, &KEY, &NONCE).unwrap_err().into();
+        let code = js_sys::Reflect::get(&js_value, &JsValue::from_str("code")).unwrap();
+        assert_eq!(code.as_string().unwrap(), "DECRYPT_FAILED");
+    }
+
+    #[wasm_bindgen_test]
+    fn wrong_key_length_is_rejected() {
+        assert!(encrypt(b"data", &[0u8; 16], &NONCE).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn wrong_nonce_length_is_rejected() {
+        assert!(encrypt(b"data", &KEY, &[0u8; 8]).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn same_passphrase_and_salt_derive_the_same_key() {
+        let salt = b"a fixed salt";
+        let a = derive_key("correct horse battery staple", salt, 1000).unwrap();
+        let b = derive_key("correct horse battery staple", salt, 1000).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.len(), KEY_LEN);
+    }
+
+    #[wasm_bindgen_test]
+    fn different_salt_derives_a_different_key() {
+        let a = derive_key("correct horse battery staple", b"salt one", 1000).unwrap();
+        let b = derive_key("correct horse battery staple", b"salt two", 1000).unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[wasm_bindgen_test]
+    fn empty_passphrase_is_rejected() {
+        assert!(derive_key("", b"a fixed salt", 1000).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn too_short_salt_is_rejected() {
+        assert!(derive_key("correct horse battery staple", b"short", 1000).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn hmac_sign_and_verify_round_trip() {
+        let data = b"a blob synced to an untrusted remote";
+        let tag = hmac_sign(data, &KEY);
+        assert!(hmac_verify(data, &KEY, &tag));
+    }
+
+    #[wasm_bindgen_test]
+    fn a_flipped_data_byte_fails_hmac_verification() {
+        let mut data = b"a blob synced to an untrusted remote".to_vec();
+        let tag = hmac_sign(&data, &KEY);
+        data[0] ^= 0x01;
+        assert!(!hmac_verify(&data, &KEY, &tag));
+    }
+
+    #[wasm_bindgen_test]
+    fn a_flipped_tag_byte_fails_hmac_verification() {
+        let data = b"a blob synced to an untrusted remote";
+        let mut tag = hmac_sign(data, &KEY);
+        tag[0] ^= 0x01;
+        assert!(!hmac_verify(data, &KEY, &tag));
+    }
+
+    #[wasm_bindgen_test]
+    fn a_different_key_fails_hmac_verification() {
+        let data = b"a blob synced to an untrusted remote";
+        let tag = hmac_sign(data, &KEY);
+        let other_key = [8u8; KEY_LEN];
+        assert!(!hmac_verify(data, &other_key, &tag));
+    }
+}
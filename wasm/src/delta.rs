@@ -0,0 +1,277 @@
+//! rsync-style binary delta encoding so vault sync only has to upload the
+//! byte ranges that actually changed between two versions of a note or
+//! attachment.
+//!
+//! The base buffer is split into fixed-size blocks, each keyed by a weak
+//! rolling checksum backed up by a BLAKE3 strong hash. The target buffer is
+//! scanned byte-by-byte with the same rolling checksum; a hit (confirmed by
+//! the strong hash) emits a `Copy` token and jumps the window forward by a
+//! full block, a miss emits the skipped byte as part of a coalesced
+//! `Literal` run and advances by one.
+
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::hashing::content_hash;
+
+/// Block size used when the caller doesn't specify one.
+pub const DEFAULT_BLOCK_SIZE: u32 = 4096;
+
+/// Modulus for the weak rolling checksum (the classic rsync scheme).
+const WEAK_MODULUS: u32 = 1 << 16;
+
+#[derive(Clone, Copy)]
+struct WeakChecksum {
+    a: u32,
+    b: u32,
+}
+
+impl WeakChecksum {
+    fn compute(block: &[u8]) -> WeakChecksum {
+        let len = block.len() as u32;
+        let mut a = 0u32;
+        let mut b = 0u32;
+        for (i, &byte) in block.iter().enumerate() {
+            a = (a + byte as u32) % WEAK_MODULUS;
+            b = (b + (len - i as u32) * byte as u32) % WEAK_MODULUS;
+        }
+        WeakChecksum { a, b }
+    }
+
+    /// Roll the checksum forward by one byte: `out_byte` leaves the window,
+    /// `in_byte` enters it. O(1) regardless of block size.
+    fn roll(&self, block_len: u32, out_byte: u8, in_byte: u8) -> WeakChecksum {
+        let m = WEAK_MODULUS as i64;
+        let a = (self.a as i64 - out_byte as i64 + in_byte as i64).rem_euclid(m) as u32;
+        let b = (self.b as i64 - block_len as i64 * out_byte as i64 + a as i64).rem_euclid(m) as u32;
+        WeakChecksum { a, b }
+    }
+
+    fn value(&self) -> u32 {
+        self.a + (self.b << 16)
+    }
+}
+
+struct BlockSignature {
+    strong: String,
+}
+
+/// One opcode in a patch: copy a block from the base buffer, or emit
+/// literal bytes that weren't found in it.
+enum Token {
+    Copy(u32),
+    Literal(Vec<u8>),
+}
+
+const TAG_COPY: u8 = 0;
+const TAG_LITERAL: u8 = 1;
+
+fn signatures(base: &[u8], block_size: usize) -> (Vec<BlockSignature>, HashMap<u32, Vec<usize>>) {
+    let mut sigs = Vec::new();
+    let mut table: HashMap<u32, Vec<usize>> = HashMap::new();
+    for (index, chunk) in base.chunks(block_size).enumerate() {
+        let weak = WeakChecksum::compute(chunk).value();
+        table.entry(weak).or_default().push(index);
+        sigs.push(BlockSignature {
+            strong: content_hash(chunk),
+        });
+    }
+    (sigs, table)
+}
+
+fn push_literal_byte(tokens: &mut Vec<Token>, byte: u8) {
+    if let Some(Token::Literal(bytes)) = tokens.last_mut() {
+        bytes.push(byte);
+    } else {
+        tokens.push(Token::Literal(vec![byte]));
+    }
+}
+
+fn compute_delta_tokens(base: &[u8], target: &[u8], block_size: usize) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let n = target.len();
+    if n == 0 {
+        return tokens;
+    }
+
+    let (sigs, table) = signatures(base, block_size);
+
+    let mut window_start = 0usize;
+    let mut window_len = block_size.min(n);
+    let mut weak = WeakChecksum::compute(&target[window_start..window_start + window_len]);
+
+    loop {
+        let matched = if window_len == block_size {
+            table.get(&weak.value()).and_then(|candidates| {
+                let strong = content_hash(&target[window_start..window_start + window_len]);
+                candidates.iter().copied().find(|&idx| sigs[idx].strong == strong)
+            })
+        } else {
+            None
+        };
+
+        if let Some(idx) = matched {
+            tokens.push(Token::Copy(idx as u32));
+            window_start += window_len;
+        } else {
+            push_literal_byte(&mut tokens, target[window_start]);
+            window_start += 1;
+        }
+
+        if window_start >= n {
+            break;
+        }
+
+        window_len = (n - window_start).min(block_size);
+        if matched.is_some() || window_len != block_size {
+            // Either we just jumped past a copied block, or we've entered
+            // the final partial block: recompute fresh instead of rolling.
+            weak = WeakChecksum::compute(&target[window_start..window_start + window_len]);
+        } else {
+            let out_byte = target[window_start - 1];
+            let in_byte = target[window_start + window_len - 1];
+            weak = weak.roll(window_len as u32, out_byte, in_byte);
+        }
+    }
+
+    tokens
+}
+
+fn encode(tokens: &[Token]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for token in tokens {
+        match token {
+            Token::Copy(index) => {
+                out.push(TAG_COPY);
+                out.extend_from_slice(&index.to_le_bytes());
+            }
+            Token::Literal(bytes) => {
+                out.push(TAG_LITERAL);
+                out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+                out.extend_from_slice(bytes);
+            }
+        }
+    }
+    out
+}
+
+fn decode(patch: &[u8]) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut cursor = 0usize;
+    while cursor < patch.len() {
+        let tag = patch[cursor];
+        cursor += 1;
+        match tag {
+            TAG_COPY => {
+                let bytes = patch.get(cursor..cursor + 4).ok_or("truncated copy token")?;
+                let index = u32::from_le_bytes(bytes.try_into().unwrap());
+                cursor += 4;
+                tokens.push(Token::Copy(index));
+            }
+            TAG_LITERAL => {
+                let len_bytes = patch.get(cursor..cursor + 4).ok_or("truncated literal length")?;
+                let len = u32::from_le_bytes(len_bytes.try_into().unwrap()) as usize;
+                cursor += 4;
+                let data = patch
+                    .get(cursor..cursor + len)
+                    .ok_or("truncated literal data")?
+                    .to_vec();
+                cursor += len;
+                tokens.push(Token::Literal(data));
+            }
+            other => return Err(format!("unknown patch token tag {other}")),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Compute an rsync-style binary delta that turns `base` into `target`,
+/// using `DEFAULT_BLOCK_SIZE`-byte blocks.
+#[wasm_bindgen]
+pub fn compute_delta(base: &[u8], target: &[u8]) -> Vec<u8> {
+    compute_delta_with_block_size(base, target, DEFAULT_BLOCK_SIZE)
+}
+
+/// Same as [`compute_delta`] but with a caller-chosen block size. The same
+/// block size must be passed to [`apply_delta_with_block_size`].
+#[wasm_bindgen]
+pub fn compute_delta_with_block_size(base: &[u8], target: &[u8], block_size: u32) -> Vec<u8> {
+    let block_size = (block_size as usize).max(1);
+    encode(&compute_delta_tokens(base, target, block_size))
+}
+
+/// Reconstruct the target bytes from `base` and a patch produced by
+/// [`compute_delta`].
+#[wasm_bindgen]
+pub fn apply_delta(base: &[u8], patch: &[u8]) -> Result<Vec<u8>, JsValue> {
+    apply_delta_with_block_size(base, patch, DEFAULT_BLOCK_SIZE)
+}
+
+/// Same as [`apply_delta`] but for a patch produced with a non-default
+/// block size.
+#[wasm_bindgen]
+pub fn apply_delta_with_block_size(base: &[u8], patch: &[u8], block_size: u32) -> Result<Vec<u8>, JsValue> {
+    let block_size = (block_size as usize).max(1);
+    let tokens = decode(patch).map_err(|err| JsValue::from_str(&err))?;
+
+    let mut out = Vec::new();
+    for token in tokens {
+        match token {
+            Token::Copy(index) => {
+                let start = (index as usize)
+                    .checked_mul(block_size)
+                    .filter(|&start| start < base.len())
+                    .ok_or_else(|| JsValue::from_str("copy token index out of range"))?;
+                let end = (start + block_size).min(base.len());
+                out.extend_from_slice(&base[start..end]);
+            }
+            Token::Literal(bytes) => out.extend_from_slice(&bytes),
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn roundtrip(base: &[u8], target: &[u8], block_size: u32) {
+        let patch = compute_delta_with_block_size(base, target, block_size);
+        let rebuilt = apply_delta_with_block_size(base, &patch, block_size).unwrap();
+        assert_eq!(rebuilt, target);
+    }
+
+    #[wasm_bindgen_test]
+    fn identical_buffers_roundtrip() {
+        let data = b"the quick brown fox jumps over the lazy dog".to_vec();
+        roundtrip(&data, &data, 8);
+    }
+
+    #[wasm_bindgen_test]
+    fn empty_base_is_all_literal() {
+        roundtrip(&[], b"brand new content", 8);
+    }
+
+    #[wasm_bindgen_test]
+    fn insertion_in_the_middle_roundtrips() {
+        let base = b"AAAAAAAABBBBBBBBCCCCCCCC".to_vec();
+        let target = b"AAAAAAAAXXXXBBBBBBBBCCCCCCCC".to_vec();
+        roundtrip(&base, &target, 8);
+    }
+
+    #[wasm_bindgen_test]
+    fn trailing_partial_block_roundtrips() {
+        let base = b"AAAAAAAABBBBBBBB".to_vec();
+        let target = b"AAAAAAAABBBBBBBBtail".to_vec();
+        roundtrip(&base, &target, 8);
+    }
+
+    #[wasm_bindgen_test]
+    fn out_of_range_copy_token_is_rejected() {
+        let base = b"short".to_vec();
+        let bogus_patch = encode(&[Token::Copy(5_000_000)]);
+        assert!(apply_delta_with_block_size(&base, &bogus_patch, 8).is_err());
+    }
+}
@@ -0,0 +1,294 @@
+//! Minimal line diff between two text versions, using the Myers O(ND)
+//! algorithm, so syncing an edited note can show and transmit just the
+//! changed lines instead of the whole file.
+
+use wasm_bindgen::prelude::*;
+
+/// One line-level edit in a diff, exposed to JS.
+///
+/// `old_start..old_end` and `new_start..new_end` are line-index ranges
+/// (exclusive end) into the old and new text respectively; an `insert`
+/// has an empty old range and a `delete` has an empty new range.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffOp {
+    kind: String,
+    old_start: u32,
+    old_end: u32,
+    new_start: u32,
+    new_end: u32,
+    text: String,
+}
+
+#[wasm_bindgen]
+impl DiffOp {
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> String {
+        self.kind.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn old_start(&self) -> u32 {
+        self.old_start
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn old_end(&self) -> u32 {
+        self.old_end
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn new_start(&self) -> u32 {
+        self.new_start
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn new_end(&self) -> u32 {
+        self.new_end
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn text(&self) -> String {
+        self.text.clone()
+    }
+}
+
+/// Split text into lines the way a diff needs to: unlike `str::lines`,
+/// this preserves the distinction between a trailing newline being
+/// present or absent, so "a\nb" and "a\nb\n" produce different line
+/// counts instead of comparing equal.
+pub(crate) fn split_lines(text: &str) -> Vec<&str> {
+    if text.is_empty() {
+        Vec::new()
+    } else {
+        text.split('\n').collect()
+    }
+}
+
+#[derive(Clone, Copy)]
+enum LineEdit {
+    /// Line `ai` in `a` equals line `bi` in `b`.
+    Equal { ai: usize, bi: usize },
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Run Myers' O(ND) greedy algorithm and return the line-index edit
+/// script (in order) that turns `a` into `b`.
+fn myers_edit_script(a: &[&str], b: &[&str]) -> Vec<LineEdit> {
+    let n = a.len() as isize;
+    let m = b.len() as isize;
+    let max = (n + m).max(1);
+    let offset = max as usize;
+    let mut v = vec![0isize; 2 * max as usize + 1];
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    'search: for d in 0..=max {
+        trace.push(v.clone());
+        let mut k = -d;
+        while k <= d {
+            let idx = (k + offset as isize) as usize;
+            let mut x = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+                v[idx + 1]
+            } else {
+                v[idx - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[idx] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+            k += 2;
+        }
+    }
+
+    let mut ops = Vec::new();
+    let mut x = n;
+    let mut y = m;
+    for d in (0..trace.len()).rev() {
+        let v = &trace[d];
+        let d = d as isize;
+        let k = x - y;
+        let idx = (k + offset as isize) as usize;
+        let prev_k = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_idx = (prev_k + offset as isize) as usize;
+        let prev_x = v[prev_idx];
+        let prev_y = prev_x - prev_k;
+
+        while x > prev_x && y > prev_y {
+            ops.push(LineEdit::Equal {
+                ai: (x - 1) as usize,
+                bi: (y - 1) as usize,
+            });
+            x -= 1;
+            y -= 1;
+        }
+
+        if d > 0 {
+            if x == prev_x {
+                ops.push(LineEdit::Insert(prev_y as usize));
+            } else {
+                ops.push(LineEdit::Delete(prev_x as usize));
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    ops.reverse();
+    ops
+}
+
+/// Collapse a line-by-line edit script into contiguous `DiffOp` runs.
+fn build_ops(edits: &[LineEdit], old_lines: &[&str], new_lines: &[&str]) -> Vec<DiffOp> {
+    let mut ops = Vec::new();
+    let mut i = 0;
+    let mut old_pos = 0u32;
+    let mut new_pos = 0u32;
+
+    while i < edits.len() {
+        match edits[i] {
+            LineEdit::Equal { .. } => {
+                let (start_old, start_new) = (old_pos, new_pos);
+                let mut lines = Vec::new();
+                while let Some(LineEdit::Equal { ai, bi }) = edits.get(i) {
+                    debug_assert_eq!(old_lines[*ai], new_lines[*bi]);
+                    lines.push(old_lines[*ai]);
+                    old_pos += 1;
+                    new_pos += 1;
+                    i += 1;
+                }
+                ops.push(DiffOp {
+                    kind: "equal".to_string(),
+                    old_start: start_old,
+                    old_end: old_pos,
+                    new_start: start_new,
+                    new_end: new_pos,
+                    text: lines.join("\n"),
+                });
+            }
+            LineEdit::Delete(_) => {
+                let start_old = old_pos;
+                let mut lines = Vec::new();
+                while let Some(LineEdit::Delete(ai)) = edits.get(i) {
+                    lines.push(old_lines[*ai]);
+                    old_pos += 1;
+                    i += 1;
+                }
+                ops.push(DiffOp {
+                    kind: "delete".to_string(),
+                    old_start: start_old,
+                    old_end: old_pos,
+                    new_start: new_pos,
+                    new_end: new_pos,
+                    text: lines.join("\n"),
+                });
+            }
+            LineEdit::Insert(_) => {
+                let start_new = new_pos;
+                let mut lines = Vec::new();
+                while let Some(LineEdit::Insert(bi)) = edits.get(i) {
+                    lines.push(new_lines[*bi]);
+                    new_pos += 1;
+                    i += 1;
+                }
+                ops.push(DiffOp {
+                    kind: "insert".to_string(),
+                    old_start: old_pos,
+                    old_end: old_pos,
+                    new_start: start_new,
+                    new_end: new_pos,
+                    text: lines.join("\n"),
+                });
+            }
+        }
+    }
+    ops
+}
+
+/// Compute the minimal line-level diff turning `old` into `new`.
+#[wasm_bindgen]
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffOp> {
+    let old_lines = split_lines(old);
+    let new_lines = split_lines(new);
+    let edits = myers_edit_script(&old_lines, &new_lines);
+    build_ops(&edits, &old_lines, &new_lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn identical_inputs_are_all_equal() {
+        let ops = diff_lines("a\nb\nc", "a\nb\nc");
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].kind(), "equal");
+        assert_eq!(ops[0].text(), "a\nb\nc");
+    }
+
+    #[wasm_bindgen_test]
+    fn single_line_change_is_a_delete_and_insert() {
+        let ops = diff_lines("a\nb\nc", "a\nX\nc");
+        let kinds: Vec<_> = ops.iter().map(|op| op.kind()).collect();
+        assert_eq!(kinds, vec!["equal", "delete", "insert", "equal"]);
+        assert_eq!(ops[1].text(), "b");
+        assert_eq!(ops[2].text(), "X");
+    }
+
+    #[wasm_bindgen_test]
+    fn block_move_is_expressed_as_delete_plus_insert() {
+        let old = "a\nb\nc\nd";
+        let new = "c\nd\na\nb";
+        let ops = diff_lines(old, new);
+        // Myers has no notion of "move": it's a delete of the shifted block
+        // plus an insert elsewhere. Reassembling old_lines vs new_lines
+        // from the ops should still exactly reconstruct both sides.
+        let mut rebuilt_old = Vec::new();
+        let mut rebuilt_new = Vec::new();
+        for op in &ops {
+            match op.kind().as_str() {
+                "equal" => {
+                    rebuilt_old.push(op.text());
+                    rebuilt_new.push(op.text());
+                }
+                "delete" => rebuilt_old.push(op.text()),
+                "insert" => rebuilt_new.push(op.text()),
+                other => panic!("unexpected kind {other}"),
+            }
+        }
+        assert_eq!(rebuilt_old.join("\n"), old);
+        assert_eq!(rebuilt_new.join("\n"), new);
+    }
+
+    #[wasm_bindgen_test]
+    fn all_inserts_when_old_side_is_empty() {
+        let ops = diff_lines("", "a\nb");
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].kind(), "insert");
+        assert_eq!(ops[0].text(), "a\nb");
+    }
+
+    #[wasm_bindgen_test]
+    fn all_deletes_when_new_side_is_empty() {
+        let ops = diff_lines("a\nb", "");
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].kind(), "delete");
+        assert_eq!(ops[0].text(), "a\nb");
+    }
+
+    #[wasm_bindgen_test]
+    fn trailing_newline_difference_is_visible() {
+        let ops = diff_lines("a\nb", "a\nb\n");
+        assert_eq!(ops.last().unwrap().kind(), "insert");
+        assert_eq!(ops.last().unwrap().text(), "");
+    }
+}
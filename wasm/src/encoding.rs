@@ -0,0 +1,141 @@
+//! Base64 encode/decode at the WASM boundary.
+//!
+//! Binary deltas and encrypted blobs have to travel through JSON-based
+//! remote APIs, which means base64. Doing that encoding in JS for a large
+//! buffer is slow enough to matter, so it lives here instead.
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::SyncError;
+
+const STANDARD_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const PAD: u8 = b'=';
+
+fn alphabet(url_safe: bool) -> &'static [u8; 64] {
+    if url_safe {
+        URL_SAFE_ALPHABET
+    } else {
+        STANDARD_ALPHABET
+    }
+}
+
+/// Encode `data` as base64, using the URL-safe alphabet (`-_` in place of
+/// `+/`) when `url_safe` is true. Output is always padded with `=`.
+#[wasm_bindgen]
+pub fn to_base64(data: &[u8], url_safe: bool) -> String {
+    let table = alphabet(url_safe);
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = chunk.get(1).copied().unwrap_or(0);
+        let b2 = chunk.get(2).copied().unwrap_or(0);
+
+        out.push(table[(b0 >> 2) as usize] as char);
+        out.push(table[(((b0 & 0x03) << 4) | (b1 >> 4)) as usize] as char);
+        out.push(if chunk.len() > 1 {
+            table[(((b1 & 0x0f) << 2) | (b2 >> 6)) as usize] as char
+        } else {
+            PAD as char
+        });
+        out.push(if chunk.len() > 2 { table[(b2 & 0x3f) as usize] as char } else { PAD as char });
+    }
+
+    out
+}
+
+/// Decode a string produced by [`to_base64`] in the matching alphabet.
+/// Trailing `=` padding is optional; a character outside the chosen
+/// alphabet, or a length that can't be valid base64, is rejected instead
+/// of silently truncated.
+#[wasm_bindgen]
+pub fn from_base64(s: &str, url_safe: bool) -> Result<Vec<u8>, SyncError> {
+    let table = alphabet(url_safe);
+    let lookup = |c: u8| -> Result<u8, SyncError> {
+        table
+            .iter()
+            .position(|&t| t == c)
+            .map(|i| i as u8)
+            .ok_or_else(|| SyncError::InvalidArgument(format!("invalid base64 character: {:?}", c as char)))
+    };
+
+    let stripped = s.trim_end_matches('=');
+    let chars: Vec<u8> = stripped.bytes().collect();
+    if chars.len() % 4 == 1 {
+        return Err(SyncError::InvalidArgument(format!(
+            "invalid base64: length {} can't be valid base64",
+            s.len()
+        )));
+    }
+
+    let mut out = Vec::with_capacity(chars.len() / 4 * 3 + 3);
+    for group in chars.chunks(4) {
+        let vals: Vec<u8> = group.iter().map(|&c| lookup(c)).collect::<Result<_, _>>()?;
+
+        out.push((vals[0] << 2) | (vals[1] >> 4));
+        if let Some(&v2) = vals.get(2) {
+            out.push((vals[1] << 4) | (v2 >> 2));
+        }
+        if let Some(&v3) = vals.get(3) {
+            out.push((vals[2] << 6) | v3);
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn round_trips_empty_input() {
+        assert_eq!(to_base64(&[], false), "");
+        assert_eq!(from_base64("", false).unwrap(), Vec::<u8>::new());
+    }
+
+    #[wasm_bindgen_test]
+    fn round_trips_arbitrary_bytes_in_the_standard_alphabet() {
+        let data: Vec<u8> = (0u16..256).map(|b| b as u8).collect();
+        let encoded = to_base64(&data, false);
+        assert!(encoded.chars().all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/' || c == '='));
+        assert_eq!(from_base64(&encoded, false).unwrap(), data);
+    }
+
+    #[wasm_bindgen_test]
+    fn round_trips_arbitrary_bytes_in_the_url_safe_alphabet() {
+        let data: Vec<u8> = (0u16..256).map(|b| b as u8).collect();
+        let encoded = to_base64(&data, true);
+        assert!(encoded.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '='));
+        assert_eq!(from_base64(&encoded, true).unwrap(), data);
+    }
+
+    #[wasm_bindgen_test]
+    fn known_vector_matches_standard_base64() {
+        assert_eq!(to_base64(b"any carnal pleasure.", false), "YW55IGNhcm5hbCBwbGVhc3VyZS4=");
+        assert_eq!(from_base64("YW55IGNhcm5hbCBwbGVhc3VyZS4=", false).unwrap(), b"any carnal pleasure.");
+    }
+
+    #[wasm_bindgen_test]
+    fn decoding_tolerates_missing_padding() {
+        let data = b"pad me";
+        let padded = to_base64(data, false);
+        let unpadded = padded.trim_end_matches('=');
+        assert_eq!(from_base64(unpadded, false).unwrap(), data);
+    }
+
+    #[wasm_bindgen_test]
+    fn a_character_outside_the_alphabet_is_rejected() {
+        assert!(from_base64("not valid base64!!", false).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn standard_and_url_safe_alphabets_are_not_interchangeable() {
+        let data = &[0xfb, 0xff, 0xfe];
+        let standard = to_base64(data, false);
+        assert!(standard.contains('/') || standard.contains('+'));
+        assert!(from_base64(&standard, true).is_err());
+    }
+}
@@ -0,0 +1,106 @@
+//! A structured error for fallible functions that cross the WASM
+//! boundary, so TypeScript can branch on a machine-readable `code`
+//! instead of regex-matching a thrown string.
+//!
+//! `From<SyncError> for JsValue` (and so `Into<JsValue>`) turns a
+//! [`SyncError`] into the plain JS object `{ code, message }` that a
+//! `#[wasm_bindgen]` function returning `Result<T, SyncError>` throws on
+//! the JS side.
+
+use js_sys::{Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+/// A fallible operation's error, tagged with a machine-readable `code`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyncError {
+    /// AES-GCM decryption failed authentication, or the key/nonce didn't
+    /// match what the ciphertext was encrypted with.
+    DecryptFailed(String),
+    /// AES-GCM encryption itself failed (as opposed to a bad argument
+    /// caught before encryption is attempted).
+    EncryptFailed(String),
+    /// A binary or line-level delta/patch is truncated, malformed, or
+    /// doesn't apply cleanly to the given base.
+    CorruptDelta(String),
+    /// Compressed, serialized, or otherwise encoded data failed to parse.
+    CorruptData(String),
+    /// An argument violated a documented precondition (e.g. a key or
+    /// nonce of the wrong length).
+    InvalidArgument(String),
+    /// An internal panic was caught at the boundary instead of aborting
+    /// the WASM instance; see [`crate::guard`].
+    InternalPanic(String),
+    /// A patch's context lines don't match the base text it's being
+    /// applied to; see [`crate::patch::apply_patch`].
+    PatchRejected(String),
+    /// A reconstructed result's content hash doesn't match the hash the
+    /// sender expected, so the delta/patch applied cleanly but produced
+    /// the wrong bytes; see [`crate::patch::apply_patch_verified`].
+    HashMismatch(String),
+    /// A [`crate::session::SyncSession`] transition was attempted from a
+    /// phase it isn't legal from.
+    BadTransition(String),
+    /// A caller-supplied JS progress callback threw instead of returning.
+    CallbackFailed(String),
+    /// A [`crate::chunking::assemble`] chunk hash wasn't found in the
+    /// [`crate::store::ChunkStore`] it was reassembled against.
+    MissingChunk(String),
+}
+
+impl SyncError {
+    fn code(&self) -> &'static str {
+        match self {
+            SyncError::DecryptFailed(_) => "DECRYPT_FAILED",
+            SyncError::EncryptFailed(_) => "ENCRYPT_FAILED",
+            SyncError::CorruptDelta(_) => "CORRUPT_DELTA",
+            SyncError::CorruptData(_) => "CORRUPT_DATA",
+            SyncError::InvalidArgument(_) => "INVALID_ARGUMENT",
+            SyncError::InternalPanic(_) => "INTERNAL_PANIC",
+            SyncError::PatchRejected(_) => "PATCH_REJECTED",
+            SyncError::HashMismatch(_) => "HASH_MISMATCH",
+            SyncError::BadTransition(_) => "BAD_TRANSITION",
+            SyncError::CallbackFailed(_) => "CALLBACK_FAILED",
+            SyncError::MissingChunk(_) => "MISSING_CHUNK",
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            SyncError::DecryptFailed(message)
+            | SyncError::EncryptFailed(message)
+            | SyncError::CorruptDelta(message)
+            | SyncError::CorruptData(message)
+            | SyncError::InvalidArgument(message)
+            | SyncError::InternalPanic(message)
+            | SyncError::PatchRejected(message)
+            | SyncError::HashMismatch(message)
+            | SyncError::BadTransition(message)
+            | SyncError::CallbackFailed(message)
+            | SyncError::MissingChunk(message) => message,
+        }
+    }
+}
+
+impl From<SyncError> for JsValue {
+    fn from(err: SyncError) -> JsValue {
+        let obj = Object::new();
+        Reflect::set(&obj, &JsValue::from_str("code"), &JsValue::from_str(err.code())).unwrap();
+        Reflect::set(&obj, &JsValue::from_str("message"), &JsValue::from_str(err.message())).unwrap();
+        obj.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn converts_to_a_js_object_with_code_and_message() {
+        let js_value: JsValue = SyncError::DecryptFailed("bad tag".to_string()).into();
+        let code = Reflect::get(&js_value, &JsValue::from_str("code")).unwrap();
+        let message = Reflect::get(&js_value, &JsValue::from_str("message")).unwrap();
+        assert_eq!(code.as_string().unwrap(), "DECRYPT_FAILED");
+        assert_eq!(message.as_string().unwrap(), "bad tag");
+    }
+}
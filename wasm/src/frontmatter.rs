@@ -0,0 +1,121 @@
+//! Extract a note's leading YAML frontmatter block, so sync decisions
+//! (tags, an explicit `sync: false`, a stable `uid`) can be made without
+//! JS re-implementing the parsing rules.
+//!
+//! We only extract the raw YAML text and where the body starts — actual
+//! YAML parsing is left to JS, which already has a mature library for
+//! it, rather than pulling a YAML parser into the WASM binary for this
+//! one use.
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::SyncError;
+
+const DELIMITER: &str = "---";
+
+/// A note's frontmatter: the raw YAML between the delimiters (empty if
+/// the note has none) and the byte offset in the original content where
+/// the body starts (`0` when there's no frontmatter block).
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Frontmatter {
+    yaml: String,
+    body_offset: u32,
+}
+
+#[wasm_bindgen]
+impl Frontmatter {
+    #[wasm_bindgen(getter)]
+    pub fn yaml(&self) -> String {
+        self.yaml.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn body_offset(&self) -> u32 {
+        self.body_offset
+    }
+}
+
+/// Parse the frontmatter block at the very start of `content`, if any.
+///
+/// Only a `---` that is the document's first line opens a block — a
+/// horizontal rule written as `---` further down is just body text, not
+/// mistaken frontmatter, since we never look for the opening delimiter
+/// past position zero. A note with no leading `---` gets back an empty
+/// [`Frontmatter`], not an error; only a block that's opened but never
+/// closed with a matching `---` line is an error.
+#[wasm_bindgen]
+pub fn parse_frontmatter(content: &str) -> Result<Frontmatter, SyncError> {
+    let mut lines = content.split_inclusive('\n');
+
+    let Some(first_line) = lines.next() else {
+        return Ok(Frontmatter::default());
+    };
+    if trim_line_ending(first_line) != DELIMITER {
+        return Ok(Frontmatter::default());
+    }
+
+    let yaml_start = first_line.len();
+    let mut offset = yaml_start;
+    for line in lines {
+        if trim_line_ending(line) == DELIMITER {
+            return Ok(Frontmatter {
+                yaml: content[yaml_start..offset].to_string(),
+                body_offset: (offset + line.len()) as u32,
+            });
+        }
+        offset += line.len();
+    }
+
+    Err(SyncError::CorruptData(
+        "unterminated frontmatter block: missing closing '---'".to_string(),
+    ))
+}
+
+fn trim_line_ending(line: &str) -> &str {
+    line.trim_end_matches(['\n', '\r'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn parses_a_note_with_frontmatter() {
+        let content = "---\ntags: [inbox]\nuid: abc123\n---\n# Title\n\nBody text.\n";
+        let frontmatter = parse_frontmatter(content).unwrap();
+        assert_eq!(frontmatter.yaml(), "tags: [inbox]\nuid: abc123\n");
+        assert_eq!(&content[frontmatter.body_offset() as usize..], "# Title\n\nBody text.\n");
+    }
+
+    #[wasm_bindgen_test]
+    fn a_note_with_no_frontmatter_returns_an_empty_block_not_an_error() {
+        let content = "# Title\n\nJust a normal note.\n";
+        let frontmatter = parse_frontmatter(content).unwrap();
+        assert_eq!(frontmatter.yaml(), "");
+        assert_eq!(frontmatter.body_offset(), 0);
+    }
+
+    #[wasm_bindgen_test]
+    fn a_horizontal_rule_is_not_mistaken_for_frontmatter() {
+        let content = "# Title\n\nSome text.\n\n---\n\nMore text below the rule.\n";
+        let frontmatter = parse_frontmatter(content).unwrap();
+        assert_eq!(frontmatter.yaml(), "");
+        assert_eq!(frontmatter.body_offset(), 0);
+    }
+
+    #[wasm_bindgen_test]
+    fn an_unterminated_block_is_an_error() {
+        let content = "---\ntags: [inbox]\n# Title\n";
+        assert!(parse_frontmatter(content).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn an_empty_frontmatter_block_yields_an_empty_yaml_string() {
+        let content = "---\n---\nBody.\n";
+        let frontmatter = parse_frontmatter(content).unwrap();
+        assert_eq!(frontmatter.yaml(), "");
+        assert_eq!(&content[frontmatter.body_offset() as usize..], "Body.\n");
+    }
+}
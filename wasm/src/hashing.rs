@@ -0,0 +1,315 @@
+//! Collision-resistant content hashing for sync dedup.
+//!
+//! `compute_hash` in the crate root is a demo polynomial hash and is not
+//! safe to use for deciding whether two vault files are identical. This
+//! module provides the real, cryptographic replacement.
+
+use js_sys::Function;
+use wasm_bindgen::prelude::*;
+
+use crate::error::SyncError;
+
+/// How many items [`hash_batch_with_progress`] hashes between progress
+/// callback invocations. Calling into JS is comparatively expensive, so
+/// this keeps a vault scan of thousands of small files from tanking
+/// performance by reporting on every single one.
+const PROGRESS_THROTTLE: usize = 64;
+
+/// Hex-encoded BLAKE3 digest of `input`.
+///
+/// The output is stable across platforms (independent of endianness) and
+/// across crate versions, so it's safe to persist in a manifest and
+/// compare hashes computed at different times.
+#[wasm_bindgen]
+pub fn content_hash(input: &[u8]) -> String {
+    blake3::hash(input).to_hex().to_string()
+}
+
+/// Like [`content_hash`], but mixes in `domain` first, so two different
+/// uses of hashing (e.g. path hashing, content hashing, chunk hashing)
+/// never collide into the same namespace even if they happen to hash the
+/// same bytes. `domain` is itself hashed into a fixed-length tag before
+/// being combined with `input`, so no `(domain, input)` pair can be made
+/// to collide with a different pair by shuffling bytes between the two.
+///
+/// The empty domain (`""`) is defined to be identical to plain
+/// [`content_hash`], so existing manifests hashed before domain
+/// separation existed stay valid without a migration.
+#[wasm_bindgen]
+pub fn content_hash_domain(input: &[u8], domain: &str) -> String {
+    if domain.is_empty() {
+        return content_hash(input);
+    }
+
+    let tag = content_hash(domain.as_bytes());
+    let mut tagged = Vec::with_capacity(tag.len() + input.len());
+    tagged.extend_from_slice(tag.as_bytes());
+    tagged.extend_from_slice(input);
+    content_hash(&tagged)
+}
+
+/// Hash many inputs in a single WASM call, so an initial vault scan of
+/// thousands of small files doesn't pay the JS↔WASM call overhead once
+/// per file. Reuses one [`blake3::Hasher`] across inputs (resetting it
+/// between them) instead of allocating a fresh hasher per input.
+#[wasm_bindgen]
+pub fn hash_batch(inputs: Vec<String>) -> Vec<String> {
+    let mut hasher = blake3::Hasher::new();
+    inputs
+        .into_iter()
+        .map(|input| {
+            hasher.reset();
+            hasher.update(input.as_bytes());
+            hasher.finalize().to_hex().to_string()
+        })
+        .collect()
+}
+
+/// Like [`hash_batch`], but invokes `progress` (if given) with
+/// `(done, total)` every [`PROGRESS_THROTTLE`] items and once more at the
+/// end, so a UI hashing a huge vault can show a progress bar instead of
+/// freezing with no feedback. If `progress` throws, that error aborts the
+/// whole batch instead of being swallowed.
+#[wasm_bindgen]
+pub fn hash_batch_with_progress(inputs: Vec<String>, progress: Option<Function>) -> Result<Vec<String>, SyncError> {
+    let total = inputs.len();
+    let mut hasher = blake3::Hasher::new();
+    let mut results = Vec::with_capacity(total);
+
+    for (i, input) in inputs.into_iter().enumerate() {
+        hasher.reset();
+        hasher.update(input.as_bytes());
+        results.push(hasher.finalize().to_hex().to_string());
+
+        let done = i + 1;
+        if let Some(callback) = &progress {
+            if done % PROGRESS_THROTTLE == 0 || done == total {
+                report_progress(callback, done, total)?;
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+fn report_progress(callback: &Function, done: usize, total: usize) -> Result<(), SyncError> {
+    callback
+        .call2(&JsValue::NULL, &JsValue::from(done as u32), &JsValue::from(total as u32))
+        .map(|_| ())
+        .map_err(|err| {
+            SyncError::CallbackFailed(err.as_string().unwrap_or_else(|| "progress callback threw".to_string()))
+        })
+}
+
+/// Incremental BLAKE3 hasher for streaming large attachments across the
+/// WASM boundary a chunk at a time, instead of buffering the whole file
+/// into linear memory first.
+#[wasm_bindgen]
+pub struct Hasher {
+    inner: blake3::Hasher,
+}
+
+#[wasm_bindgen]
+impl Hasher {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Hasher {
+        Hasher {
+            inner: blake3::Hasher::new(),
+        }
+    }
+
+    /// Feed the next chunk of bytes into the running hash.
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.inner.update(chunk);
+    }
+
+    /// Consume the hasher and return the final digest as hex. Taking
+    /// `self` by value means calling `finalize` twice is a compile-time
+    /// error in Rust callers, and wasm-bindgen enforces the same on the JS
+    /// side by invalidating the object after the call.
+    pub fn finalize(self) -> String {
+        self.inner.finalize().to_hex().to_string()
+    }
+}
+
+impl Default for Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A file's composite hash, built from its chunks' individual hashes
+/// (e.g. from [`crate::chunking::chunk_content`]) instead of the file's
+/// full bytes.
+///
+/// Rehashing a whole large file from scratch after only one chunk changed
+/// wastes the work already done hashing every unchanged chunk.
+/// `replace_chunk` only touches the one entry that changed, so
+/// [`FileHash::digest`] stays cheap to recompute: it hashes the list of
+/// (already-known) chunk hashes, not the file's raw content.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileHash {
+    chunk_hashes: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl FileHash {
+    /// Build a `FileHash` from a file's chunk hashes, in file order.
+    #[wasm_bindgen(constructor)]
+    pub fn new(chunk_hashes: Vec<String>) -> FileHash {
+        FileHash { chunk_hashes }
+    }
+
+    /// Replace the hash of the chunk at `index`. Returns an error instead
+    /// of panicking if `index` is out of range.
+    pub fn replace_chunk(&mut self, index: usize, new_chunk_hash: &str) -> Result<(), SyncError> {
+        let total = self.chunk_hashes.len();
+        let slot = self
+            .chunk_hashes
+            .get_mut(index)
+            .ok_or_else(|| SyncError::InvalidArgument(format!("chunk index {index} out of range for a file with {total} chunks")))?;
+        *slot = new_chunk_hash.to_string();
+        Ok(())
+    }
+
+    /// The file's composite hash: [`content_hash`] of its chunk hashes
+    /// joined in order, so it's deterministic in the chunks' concatenation
+    /// order and changes if any chunk's hash, position, or count changes.
+    pub fn digest(&self) -> String {
+        content_hash(self.chunk_hashes.join("\0").as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::*;
+
+    use super::*;
+
+    #[wasm_bindgen_test]
+    fn known_vectors_hash_to_exact_hex() {
+        assert_eq!(
+            content_hash(b""),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+        assert_eq!(
+            content_hash(b"abc"),
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn identical_input_hashes_identically() {
+        assert_eq!(content_hash(b"test"), content_hash(b"test"));
+        assert_ne!(content_hash(b"test"), content_hash(b"different"));
+    }
+
+    #[wasm_bindgen_test]
+    fn streamed_hash_matches_one_shot_across_chunk_boundaries() {
+        let data = b"the quick brown fox jumps over the lazy dog";
+        let expected = content_hash(data);
+
+        for split in [0, 1, 4, data.len() / 2, data.len() - 1, data.len()] {
+            let mut hasher = Hasher::new();
+            hasher.update(&data[..split]);
+            hasher.update(&data[split..]);
+            assert_eq!(hasher.finalize(), expected);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn the_empty_domain_matches_plain_content_hash() {
+        assert_eq!(content_hash_domain(b"abc", ""), content_hash(b"abc"));
+        assert_eq!(content_hash_domain(b"", ""), content_hash(b""));
+    }
+
+    #[wasm_bindgen_test]
+    fn different_domains_hash_the_same_input_differently() {
+        let input = b"vault/notes/todo.md";
+        let path_hash = content_hash_domain(input, "path");
+        let content_hash_value = content_hash_domain(input, "content");
+        let chunk_hash = content_hash_domain(input, "chunk");
+
+        assert_ne!(path_hash, content_hash_value);
+        assert_ne!(path_hash, chunk_hash);
+        assert_ne!(content_hash_value, chunk_hash);
+        assert_ne!(path_hash, content_hash(input));
+    }
+
+    #[wasm_bindgen_test]
+    fn the_same_domain_and_input_hash_identically() {
+        assert_eq!(content_hash_domain(b"abc", "path"), content_hash_domain(b"abc", "path"));
+    }
+
+    #[wasm_bindgen_test]
+    fn hash_batch_is_empty_for_an_empty_input_and_matches_content_hash_in_order() {
+        assert_eq!(hash_batch(Vec::new()), Vec::<String>::new());
+
+        let inputs = vec!["one".to_string(), "".to_string(), "three".to_string()];
+        let expected: Vec<String> = inputs.iter().map(|input| content_hash(input.as_bytes())).collect();
+        assert_eq!(hash_batch(inputs), expected);
+    }
+
+    #[wasm_bindgen_test]
+    fn progress_callback_final_call_reports_done_equals_total() {
+        let calls = Rc::new(RefCell::new(Vec::<(u32, u32)>::new()));
+        let recorder = calls.clone();
+        let closure = Closure::wrap(Box::new(move |done: u32, total: u32| {
+            recorder.borrow_mut().push((done, total));
+        }) as Box<dyn FnMut(u32, u32)>);
+
+        let inputs: Vec<String> = (0..200).map(|i| format!("item-{i}")).collect();
+        let total = inputs.len();
+        let result =
+            hash_batch_with_progress(inputs, Some(closure.as_ref().unchecked_ref::<Function>().clone())).unwrap();
+
+        assert_eq!(result, hash_batch((0..total).map(|i| format!("item-{i}")).collect()));
+
+        let calls = calls.borrow();
+        assert!(!calls.is_empty());
+        assert_eq!(*calls.last().unwrap(), (total as u32, total as u32));
+    }
+
+    #[wasm_bindgen_test]
+    fn a_throwing_callback_aborts_the_batch_with_an_error() {
+        let callback = Function::new_with_args("done, total", "throw new Error('deliberate test failure')");
+
+        let inputs: Vec<String> = (0..10).map(|i| format!("item-{i}")).collect();
+        let err = hash_batch_with_progress(inputs, Some(callback)).unwrap_err();
+        match err {
+            SyncError::CallbackFailed(_) => {}
+            other => panic!("expected Err(SyncError::CallbackFailed(_)), got {other:?}"),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn digest_matches_hashing_the_chunk_hashes_in_order() {
+        let chunk_hashes = vec!["aaa".to_string(), "bbb".to_string(), "ccc".to_string()];
+        let file_hash = FileHash::new(chunk_hashes.clone());
+        assert_eq!(file_hash.digest(), content_hash(chunk_hashes.join("\0").as_bytes()));
+    }
+
+    #[wasm_bindgen_test]
+    fn replace_chunk_then_replace_back_restores_the_original_digest() {
+        let mut file_hash = FileHash::new(vec!["aaa".to_string(), "bbb".to_string(), "ccc".to_string()]);
+        let original = file_hash.digest();
+
+        file_hash.replace_chunk(1, "modified").unwrap();
+        assert_ne!(file_hash.digest(), original);
+
+        file_hash.replace_chunk(1, "bbb").unwrap();
+        assert_eq!(file_hash.digest(), original);
+    }
+
+    #[wasm_bindgen_test]
+    fn replace_chunk_with_an_out_of_range_index_is_rejected() {
+        let mut file_hash = FileHash::new(vec!["aaa".to_string()]);
+        assert!(file_hash.replace_chunk(5, "anything").is_err());
+    }
+}
@@ -0,0 +1,265 @@
+//! Gitignore-style ignore patterns for selective sync, so users can keep
+//! `.trash/`, `.obsidian/workspace.json`, and build folders out of their
+//! synced vault without us hard-coding a denylist.
+//!
+//! Matching works on `/`-separated path segments regardless of platform,
+//! since a manifest path is always written with forward slashes (see
+//! [`crate::manifest`]).
+
+use wasm_bindgen::prelude::*;
+
+use crate::manifest::Manifest;
+
+/// One compiled ignore rule.
+struct Rule {
+    /// `true` for a `!`-prefixed pattern: a later match un-ignores the path.
+    negated: bool,
+    /// `true` for a pattern with no `/` other than a possible trailing
+    /// one — it matches at any depth, as if prefixed with `**/`.
+    unanchored: bool,
+    /// `true` for a pattern ending in `/` — it only ever matches a
+    /// directory (and, by extension, everything under it).
+    dir_only: bool,
+    /// Pattern split on `/`, with any leading/trailing `/` already
+    /// stripped off.
+    segments: Vec<String>,
+}
+
+impl Rule {
+    fn parse(line: &str) -> Option<Rule> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+
+        let dir_only = line.ends_with('/') && line.len() > 1;
+        let line = line.strip_suffix('/').unwrap_or(line);
+
+        let anchored = line.starts_with('/');
+        let line = line.strip_prefix('/').unwrap_or(line);
+        let unanchored = !anchored && !line.contains('/');
+
+        Some(Rule {
+            negated,
+            unanchored,
+            dir_only,
+            segments: line.split('/').map(str::to_string).collect(),
+        })
+    }
+
+    /// Does this rule apply to `path_segments`, treating it as a file
+    /// path (directory-only rules also match any of its ancestors)?
+    fn matches(&self, path_segments: &[&str]) -> bool {
+        let pattern_segments: Vec<&str> = if self.unanchored {
+            std::iter::once("**")
+                .chain(self.segments.iter().map(String::as_str))
+                .collect()
+        } else {
+            self.segments.iter().map(String::as_str).collect()
+        };
+
+        if self.dir_only {
+            (1..=path_segments.len())
+                .any(|end| segments_match(&pattern_segments, &path_segments[..end]))
+        } else {
+            segments_match(&pattern_segments, path_segments)
+        }
+    }
+}
+
+/// Match `pattern` (segments already split on `/`, `**` allowed) against
+/// `path` (also split on `/`), where `**` consumes zero or more segments
+/// and `*`/`?` inside a segment never cross a `/`.
+fn segments_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.first() {
+        None => path.is_empty(),
+        Some(&"**") => {
+            segments_match(&pattern[1..], path)
+                || (!path.is_empty() && segments_match(pattern, &path[1..]))
+        }
+        Some(&head) => {
+            !path.is_empty() && segment_match(head, path[0]) && segments_match(&pattern[1..], &path[1..])
+        }
+    }
+}
+
+/// Match a single path segment against a single pattern segment
+/// containing `*` (any run of characters) and `?` (any one character).
+fn segment_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    segment_match_from(&pattern, &text)
+}
+
+fn segment_match_from(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') => {
+            (0..=text.len()).any(|split| segment_match_from(&pattern[1..], &text[split..]))
+        }
+        Some('?') => !text.is_empty() && segment_match_from(&pattern[1..], &text[1..]),
+        Some(&c) => !text.is_empty() && text[0] == c && segment_match_from(&pattern[1..], &text[1..]),
+    }
+}
+
+/// A compiled set of gitignore-style patterns.
+#[wasm_bindgen]
+pub struct IgnoreSet {
+    rules: Vec<Rule>,
+}
+
+#[wasm_bindgen]
+impl IgnoreSet {
+    /// Compile one pattern per non-empty, non-comment line. Blank lines
+    /// and lines starting with `#` are skipped, matching gitignore.
+    pub fn from_lines(patterns: &str) -> IgnoreSet {
+        IgnoreSet {
+            rules: patterns.lines().filter_map(Rule::parse).collect(),
+        }
+    }
+
+    /// Is `path` ignored? Rules are evaluated in order, and whichever
+    /// rule matched last decides the outcome — so a later `!pattern` can
+    /// un-ignore a path an earlier pattern excluded.
+    pub fn is_ignored(&self, path: &str) -> bool {
+        let path = path.trim_matches('/');
+        let path_segments: Vec<&str> = path.split('/').collect();
+
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.matches(&path_segments) {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+}
+
+/// Match `pattern` (`*` for a single segment, `**` for any number of
+/// segments, `?` for a single character) against the whole of `path`,
+/// anchored to the root — unlike [`IgnoreSet`], there's no implicit
+/// `**/` prefix, since a selective-sync pattern names an exact location
+/// rather than "anywhere named this".
+#[wasm_bindgen]
+pub fn glob_match(pattern: &str, path: &str, case_insensitive: bool) -> bool {
+    let (pattern, path) = if case_insensitive {
+        (pattern.to_lowercase(), path.to_lowercase())
+    } else {
+        (pattern.to_string(), path.to_string())
+    };
+    let pattern_segments: Vec<&str> = pattern.trim_matches('/').split('/').collect();
+    let path_segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    segments_match(&pattern_segments, &path_segments)
+}
+
+/// Return every path in `manifest` matching `pattern` (case-sensitive),
+/// sorted — the paths a selective sync like `Journal/**/*.md` should
+/// actually touch.
+#[wasm_bindgen]
+pub fn filter_manifest(manifest: &Manifest, pattern: &str) -> Vec<String> {
+    manifest
+        .entries
+        .keys()
+        .filter(|path| glob_match(pattern, path, false))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn plain_pattern_matches_at_any_depth() {
+        let set = IgnoreSet::from_lines("*.tmp");
+        assert!(set.is_ignored("scratch.tmp"));
+        assert!(set.is_ignored("notes/scratch.tmp"));
+        assert!(!set.is_ignored("notes/scratch.md"));
+    }
+
+    #[wasm_bindgen_test]
+    fn leading_slash_anchors_to_the_root() {
+        let set = IgnoreSet::from_lines("/build");
+        assert!(set.is_ignored("build"));
+        assert!(!set.is_ignored("notes/build"));
+    }
+
+    #[wasm_bindgen_test]
+    fn trailing_slash_ignores_the_directory_and_its_contents() {
+        let set = IgnoreSet::from_lines(".trash/");
+        assert!(set.is_ignored(".trash"));
+        assert!(set.is_ignored(".trash/deleted-note.md"));
+        assert!(set.is_ignored(".trash/nested/deleted-note.md"));
+        assert!(!set.is_ignored("not-trash/deleted-note.md"));
+    }
+
+    #[wasm_bindgen_test]
+    fn double_star_matches_across_path_segments() {
+        let set = IgnoreSet::from_lines("assets/**/*.png");
+        assert!(set.is_ignored("assets/logo.png"));
+        assert!(set.is_ignored("assets/icons/small/logo.png"));
+        assert!(!set.is_ignored("other/logo.png"));
+    }
+
+    #[wasm_bindgen_test]
+    fn later_negation_overrides_an_earlier_match() {
+        let set = IgnoreSet::from_lines("*.md\n!Journal/keep.md");
+        assert!(set.is_ignored("notes/todo.md"));
+        assert!(!set.is_ignored("Journal/keep.md"));
+    }
+
+    #[wasm_bindgen_test]
+    fn a_later_positive_pattern_can_re_ignore_after_a_negation() {
+        let set = IgnoreSet::from_lines("!*.md\n*.md");
+        assert!(set.is_ignored("todo.md"));
+    }
+
+    #[wasm_bindgen_test]
+    fn comments_and_blank_lines_are_ignored() {
+        let set = IgnoreSet::from_lines("# a comment\n\n*.log");
+        assert!(set.is_ignored("debug.log"));
+        assert!(!set.is_ignored("# a comment"));
+    }
+
+    #[wasm_bindgen_test]
+    fn glob_double_star_matches_nested_notes_but_not_other_extensions() {
+        assert!(glob_match("Journal/**/*.md", "Journal/2024/01-01.md", false));
+        assert!(glob_match("Journal/**/*.md", "Journal/01-01.md", false));
+        assert!(!glob_match("Journal/**/*.md", "Journal/note.txt", false));
+    }
+
+    #[wasm_bindgen_test]
+    fn glob_single_star_does_not_cross_a_path_separator() {
+        assert!(glob_match("Journal/*.md", "Journal/today.md", false));
+        assert!(!glob_match("Journal/*.md", "Journal/2024/today.md", false));
+    }
+
+    #[wasm_bindgen_test]
+    fn glob_question_mark_matches_exactly_one_character() {
+        assert!(glob_match("note?.md", "note1.md", false));
+        assert!(!glob_match("note?.md", "note12.md", false));
+    }
+
+    #[wasm_bindgen_test]
+    fn glob_is_case_insensitive_only_when_asked() {
+        assert!(!glob_match("journal/*.md", "Journal/Today.md", false));
+        assert!(glob_match("journal/*.md", "Journal/Today.md", true));
+    }
+
+    #[wasm_bindgen_test]
+    fn filter_manifest_returns_only_matching_paths_sorted() {
+        let mut manifest = Manifest::new();
+        manifest.add_entry("Journal/2024/01-01.md", "h1", 1, 1.0);
+        manifest.add_entry("Journal/note.txt", "h2", 1, 1.0);
+        manifest.add_entry("Inbox/idea.md", "h3", 1, 1.0);
+
+        let matched = filter_manifest(&manifest, "Journal/**/*.md");
+        assert_eq!(matched, vec!["Journal/2024/01-01.md".to_string()]);
+    }
+}
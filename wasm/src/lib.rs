@@ -1,5 +1,46 @@
+use std::panic::{self, UnwindSafe};
+
 use wasm_bindgen::prelude::*;
 
+use error::SyncError;
+
+pub mod bindelta;
+pub mod bloom;
+pub mod chunking;
+pub mod clock;
+pub mod compression;
+pub mod conflict;
+pub mod conflict_markers;
+pub mod content_hash;
+pub mod crdt;
+pub mod crypto;
+pub mod delta;
+pub mod diff;
+pub mod encoding;
+pub mod error;
+pub mod frontmatter;
+pub mod hashing;
+pub mod ignore;
+pub mod links;
+pub mod manifest;
+pub mod manifest_diff;
+pub mod merge;
+pub mod merkle;
+pub mod parallel_index;
+pub mod patch;
+pub mod paths;
+pub mod pipeline;
+pub mod ratelimit;
+pub mod retry;
+pub mod rolling;
+pub mod search;
+pub mod session;
+pub mod similarity;
+pub mod store;
+pub mod text;
+pub mod tombstone;
+pub mod vault_adapter;
+
 // This is called when the wasm module is instantiated
 // Skip during tests to avoid entry point conflicts with test harness
 #[cfg(not(test))]
@@ -12,6 +53,27 @@ pub fn main() -> Result<(), JsValue> {
     Ok(())
 }
 
+/// Run `f`, converting a panic into a [`SyncError::InternalPanic`] instead
+/// of letting it unwind across the WASM boundary.
+///
+/// An uncaught panic traps the whole WASM instance, so every call after it
+/// fails until the plugin reloads — catastrophic for a long-running sync.
+/// Route parsers and decoders handling data this build didn't produce
+/// itself (an on-disk manifest, a delta from a previous version, a peer's
+/// synced blob) through this at their public entry point. This is a
+/// last-resort safety net, not a substitute for validating input and
+/// returning a proper `Err` up front.
+pub(crate) fn guard<T>(f: impl FnOnce() -> T + UnwindSafe) -> Result<T, SyncError> {
+    panic::catch_unwind(f).map_err(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "internal panic".to_string());
+        SyncError::InternalPanic(message)
+    })
+}
+
 /// A simple test function to verify the Rust -> WASM -> TypeScript pipeline works
 #[wasm_bindgen]
 pub fn greet(name: &str) -> String {
@@ -20,7 +82,9 @@ pub fn greet(name: &str) -> String {
 
 /// Example: Calculate a hash or perform heavy computation
 /// This demonstrates why Rust + WASM is beneficial for performance
+#[deprecated(note = "not collision-resistant; use hashing::content_hash instead")]
 #[wasm_bindgen]
+#[allow(deprecated)]
 pub fn compute_hash(input: &str) -> u64 {
     // Simple hash function (not cryptographic - just for demo)
     let mut hash: u64 = 0;
@@ -41,6 +105,7 @@ mod tests {
     }
 
     #[wasm_bindgen_test]
+    #[allow(deprecated)]
     fn test_compute_hash() {
         let hash1 = compute_hash("test");
         let hash2 = compute_hash("test");
@@ -49,4 +114,18 @@ mod tests {
         assert_eq!(hash1, hash2);
         assert_ne!(hash1, hash3);
     }
+
+    #[wasm_bindgen_test]
+    fn guard_converts_a_panic_into_an_internal_panic_error() {
+        let result = guard(|| -> u32 { panic!("deliberate panic for testing") });
+        match result {
+            Err(SyncError::InternalPanic(message)) => assert!(message.contains("deliberate panic")),
+            other => panic!("expected Err(SyncError::InternalPanic(_)), got {other:?}"),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn guard_passes_through_a_non_panicking_result() {
+        assert_eq!(guard(|| 2 + 2).unwrap(), 4);
+    }
 }
@@ -0,0 +1,577 @@
+//! Extract and rewrite the links inside a note's content, so a rename
+//! can update every reference to it across the vault instead of leaving
+//! dangling links behind.
+
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+
+/// Is `line` a fenced-code-block delimiter (\`\`\` or ~~~, at least three
+/// characters, possibly indented)? Content inside a fence is verbatim
+/// code, not prose, so links inside one are left completely alone.
+fn is_fence_line(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("```") || trimmed.starts_with("~~~")
+}
+
+/// A `[[target]]` (optionally `[[target#heading|alias]]`) reference,
+/// with its byte span in the original content.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct WikiLink {
+    target: String,
+    alias: Option<String>,
+    subpath: Option<String>,
+    start: u32,
+    end: u32,
+}
+
+#[wasm_bindgen]
+impl WikiLink {
+    #[wasm_bindgen(getter)]
+    pub fn target(&self) -> String {
+        self.target.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn alias(&self) -> Option<String> {
+        self.alias.clone()
+    }
+
+    /// The `#heading` or `^block-id` suffix, including its marker
+    /// character, or `None` if the link doesn't point at a subsection.
+    #[wasm_bindgen(getter)]
+    pub fn subpath(&self) -> Option<String> {
+        self.subpath.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn start(&self) -> u32 {
+        self.start
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn end(&self) -> u32 {
+        self.end
+    }
+}
+
+/// Split `target_part` (the part of a wikilink before any `|alias`)
+/// into its bare target and an optional `#heading`/`^block` subpath.
+fn split_subpath(target_part: &str) -> (String, Option<String>) {
+    match target_part.find(['#', '^']) {
+        Some(pos) => (target_part[..pos].to_string(), Some(target_part[pos..].to_string())),
+        None => (target_part.to_string(), None),
+    }
+}
+
+fn extract_wikilinks_in_line(line: &str, line_offset: usize, out: &mut Vec<WikiLink>) {
+    let bytes = line.as_bytes();
+    let mut i = 0;
+    while i + 1 < bytes.len() {
+        if bytes[i] != b'[' || bytes[i + 1] != b'[' {
+            i += 1;
+            continue;
+        }
+        if i > 0 && bytes[i - 1] == b'\\' {
+            i += 2; // escaped `\[[`, not a link
+            continue;
+        }
+        let Some(rel_end) = line[i + 2..].find("]]") else {
+            break; // unterminated on this line
+        };
+        let inner_start = i + 2;
+        let inner_end = inner_start + rel_end;
+        let inner = &line[inner_start..inner_end];
+
+        let (target_part, alias) = match inner.split_once('|') {
+            Some((t, a)) => (t, Some(a.to_string())),
+            None => (inner, None),
+        };
+        let (target, subpath) = split_subpath(target_part);
+
+        out.push(WikiLink {
+            target,
+            alias,
+            subpath,
+            start: (line_offset + i) as u32,
+            end: (line_offset + inner_end + 2) as u32,
+        });
+        i = inner_end + 2;
+    }
+}
+
+/// Find every wikilink in `content`, skipping ones inside fenced code
+/// blocks.
+#[wasm_bindgen]
+pub fn extract_wikilinks(content: &str) -> Vec<WikiLink> {
+    let mut links = Vec::new();
+    let mut in_code_block = false;
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        if is_fence_line(line) {
+            in_code_block = !in_code_block;
+        } else if !in_code_block {
+            extract_wikilinks_in_line(line, offset, &mut links);
+        }
+        offset += line.len();
+    }
+    links
+}
+
+/// Rewrite every wikilink targeting `from` to target `to` instead,
+/// preserving each link's alias and heading/block subpath. Links inside
+/// fenced code blocks, escaped links, and links to any other target are
+/// left untouched.
+#[wasm_bindgen]
+pub fn rewrite_wikilinks(content: &str, from: &str, to: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut last = 0;
+    for link in extract_wikilinks(content) {
+        if link.target != from {
+            continue;
+        }
+        result.push_str(&content[last..link.start as usize]);
+        result.push_str("[[");
+        result.push_str(to);
+        if let Some(subpath) = &link.subpath {
+            result.push_str(subpath);
+        }
+        if let Some(alias) = &link.alias {
+            result.push('|');
+            result.push_str(alias);
+        }
+        result.push_str("]]");
+        last = link.end as usize;
+    }
+    result.push_str(&content[last..]);
+    result
+}
+
+/// A standard `[text](url)` (or `![alt](url)` embed) link, with its
+/// byte span in the original content.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MdLink {
+    text: String,
+    url: String,
+    is_embed: bool,
+    start: u32,
+    end: u32,
+}
+
+#[wasm_bindgen]
+impl MdLink {
+    #[wasm_bindgen(getter)]
+    pub fn text(&self) -> String {
+        self.text.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn is_embed(&self) -> bool {
+        self.is_embed
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn start(&self) -> u32 {
+        self.start
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn end(&self) -> u32 {
+        self.end
+    }
+}
+
+fn is_absolute_url(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+/// Decode `%XX` percent-escapes (e.g. `%20` for a space) in a link path.
+///
+/// Works on raw bytes rather than `s[i..j]` string slices: `%` can be
+/// immediately followed by a multi-byte UTF-8 character (e.g. a literal
+/// `%` next to non-ASCII text in an attachment name), and slicing a
+/// `str` at a byte offset that isn't a char boundary panics.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = &bytes[i + 1..i + 3];
+            if hex.iter().all(u8::is_ascii_hexdigit) {
+                // Both bytes are ASCII hex digits, so this is always valid UTF-8 and always parses.
+                let byte = u8::from_str_radix(std::str::from_utf8(hex).unwrap(), 16).unwrap();
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Percent-encode spaces the way Obsidian writes them in a link path.
+/// Other characters are left as-is — this only needs to round-trip
+/// paths that came from [`percent_decode`].
+fn percent_encode_spaces(s: &str) -> String {
+    s.replace(' ', "%20")
+}
+
+fn extract_md_links_in_line(line: &str, line_offset: usize, out: &mut Vec<MdLink>) {
+    let bytes = line.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'[' {
+            i += 1;
+            continue;
+        }
+        let is_embed = i > 0 && bytes[i - 1] == b'!';
+        let Some(text_len) = line[i + 1..].find(']') else {
+            i += 1;
+            continue;
+        };
+        let text_end = i + 1 + text_len;
+        if bytes.get(text_end + 1) != Some(&b'(') {
+            i += 1;
+            continue;
+        }
+        let url_start = text_end + 2;
+        let Some(url_len) = line[url_start..].find(')') else {
+            i += 1;
+            continue;
+        };
+        let url_end = url_start + url_len;
+
+        out.push(MdLink {
+            text: line[i + 1..text_end].to_string(),
+            url: line[url_start..url_end].to_string(),
+            is_embed,
+            start: (line_offset + if is_embed { i - 1 } else { i }) as u32,
+            end: (line_offset + url_end + 1) as u32,
+        });
+        i = url_end + 1;
+    }
+}
+
+/// Find every standard markdown link or embed in `content`, skipping
+/// ones inside fenced code blocks.
+#[wasm_bindgen]
+pub fn extract_md_links(content: &str) -> Vec<MdLink> {
+    let mut links = Vec::new();
+    let mut in_code_block = false;
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        if is_fence_line(line) {
+            in_code_block = !in_code_block;
+        } else if !in_code_block {
+            extract_md_links_in_line(line, offset, &mut links);
+        }
+        offset += line.len();
+    }
+    links
+}
+
+/// Rewrite every relative link/embed pointing at `from` to point at
+/// `to` instead, preserving link text and embed markers. Absolute URLs
+/// (`http://`, `https://`) are never rewritten. Paths are compared after
+/// percent-decoding, so `%20` in a link matches a literal space in
+/// `from`.
+#[wasm_bindgen]
+pub fn rewrite_md_links(content: &str, from: &str, to: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut last = 0;
+    for link in extract_md_links(content) {
+        if is_absolute_url(&link.url) || percent_decode(&link.url) != from {
+            continue;
+        }
+        result.push_str(&content[last..link.start as usize]);
+        if link.is_embed {
+            result.push('!');
+        }
+        result.push('[');
+        result.push_str(&link.text);
+        result.push_str("](");
+        result.push_str(&percent_encode_spaces(to));
+        result.push(')');
+        last = link.end as usize;
+    }
+    result.push_str(&content[last..]);
+    result
+}
+
+/// A heading found in a note, with its slugified anchor form for
+/// resolving `[[Note#Heading]]` references.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heading {
+    level: u8,
+    text: String,
+    anchor: String,
+}
+
+#[wasm_bindgen]
+impl Heading {
+    #[wasm_bindgen(getter)]
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn text(&self) -> String {
+        self.text.clone()
+    }
+
+    /// The anchor a `[[Note#Heading]]` reference to this heading should
+    /// match. Duplicate heading text is disambiguated the same way
+    /// Obsidian does: the first occurrence keeps the bare slug, later
+    /// occurrences get `-1`, `-2`, ... appended.
+    #[wasm_bindgen(getter)]
+    pub fn anchor(&self) -> String {
+        self.anchor.clone()
+    }
+}
+
+/// Every heading and `^block-id` reference target found in a note, for
+/// validating that inbound `[[Note#Heading]]` / `[[Note#^block-id]]`
+/// links still resolve after an edit.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anchors {
+    pub headings: Vec<Heading>,
+    pub block_ids: Vec<String>,
+}
+
+/// Slugify a heading the way Obsidian does: lowercase, with spaces
+/// turned into hyphens. Unlike GitHub-style slugs, other punctuation is
+/// preserved as-is rather than stripped.
+fn slugify_heading(text: &str) -> String {
+    text.to_lowercase().replace(' ', "-")
+}
+
+/// Parse a line as an ATX heading (`#` through `######`, a space, then
+/// text), returning its level and trimmed text, or `None` if the line
+/// isn't a heading.
+fn parse_heading_line(line: &str) -> Option<(u8, String)> {
+    let hashes = line.bytes().take_while(|&b| b == b'#').count();
+    if hashes == 0 || hashes > 6 {
+        return None;
+    }
+    let rest = &line[hashes..];
+    if !rest.starts_with(' ') {
+        return None;
+    }
+    let text = rest[1..].trim().to_string();
+    if text.is_empty() {
+        return None;
+    }
+    Some((hashes as u8, text))
+}
+
+/// Parse a line for a trailing `^block-id` marker, returning the id
+/// (without the `^`), or `None` if the line has no block reference.
+/// Obsidian requires the marker to be its own token at the end of the
+/// line, so `^` isn't recognized mid-word.
+fn parse_block_id(line: &str) -> Option<String> {
+    let trimmed = line.trim_end();
+    let caret = trimmed.rfind('^')?;
+    let id = &trimmed[caret + 1..];
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return None;
+    }
+    if caret > 0 && !trimmed[..caret].ends_with(char::is_whitespace) {
+        return None;
+    }
+    Some(id.to_string())
+}
+
+/// Extract every heading and block-id anchor in `content`, skipping
+/// ones inside fenced code blocks.
+#[wasm_bindgen]
+pub fn extract_anchors(content: &str) -> Anchors {
+    let mut headings = Vec::new();
+    let mut block_ids = Vec::new();
+    let mut in_code_block = false;
+    let mut seen: HashMap<String, u32> = HashMap::new();
+
+    for line in content.lines() {
+        if is_fence_line(line) {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        if let Some((level, text)) = parse_heading_line(line) {
+            let base = slugify_heading(&text);
+            let count = seen.entry(base.clone()).or_insert(0);
+            let anchor = if *count == 0 { base } else { format!("{base}-{count}") };
+            *count += 1;
+            headings.push(Heading { level, text, anchor });
+        } else if let Some(id) = parse_block_id(line) {
+            block_ids.push(id);
+        }
+    }
+
+    Anchors { headings, block_ids }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn extracts_a_plain_link() {
+        let links = extract_wikilinks("See [[Old Note]] for details.");
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].target(), "Old Note");
+        assert_eq!(links[0].alias(), None);
+        assert_eq!(links[0].subpath(), None);
+    }
+
+    #[wasm_bindgen_test]
+    fn extracts_an_aliased_link() {
+        let links = extract_wikilinks("[[Old Note|display text]]");
+        assert_eq!(links[0].target(), "Old Note");
+        assert_eq!(links[0].alias(), Some("display text".to_string()));
+    }
+
+    #[wasm_bindgen_test]
+    fn extracts_a_heading_reference() {
+        let links = extract_wikilinks("[[Old Note#Section One]]");
+        assert_eq!(links[0].target(), "Old Note");
+        assert_eq!(links[0].subpath(), Some("#Section One".to_string()));
+    }
+
+    #[wasm_bindgen_test]
+    fn rewrite_preserves_alias_and_subpath() {
+        assert_eq!(
+            rewrite_wikilinks("[[Old Note#Section|shown]]", "Old Note", "New Note"),
+            "[[New Note#Section|shown]]"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn rewrite_leaves_links_to_other_targets_untouched() {
+        let content = "[[Old Note]] and [[Other Note]]";
+        assert_eq!(rewrite_wikilinks(content, "Old Note", "New Note"), "[[New Note]] and [[Other Note]]");
+    }
+
+    #[wasm_bindgen_test]
+    fn a_link_inside_a_fenced_code_block_is_left_unchanged() {
+        let content = "Before [[Old Note]]\n```\n[[Old Note]]\n```\nAfter [[Old Note]]";
+        let links = extract_wikilinks(content);
+        assert_eq!(links.len(), 2, "the fenced link should not be extracted");
+
+        let rewritten = rewrite_wikilinks(content, "Old Note", "New Note");
+        assert_eq!(rewritten, "Before [[New Note]]\n```\n[[Old Note]]\n```\nAfter [[New Note]]");
+    }
+
+    #[wasm_bindgen_test]
+    fn an_escaped_link_is_not_extracted() {
+        let links = extract_wikilinks(r"\[[Old Note]]");
+        assert!(links.is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn extracts_an_embed() {
+        let links = extract_md_links("![](attachments/img.png)");
+        assert_eq!(links.len(), 1);
+        assert!(links[0].is_embed());
+        assert_eq!(links[0].url(), "attachments/img.png");
+    }
+
+    #[wasm_bindgen_test]
+    fn extracts_a_relative_note_link() {
+        let links = extract_md_links("See [my note](Notes/note.md) for details.");
+        assert_eq!(links.len(), 1);
+        assert!(!links[0].is_embed());
+        assert_eq!(links[0].text(), "my note");
+        assert_eq!(links[0].url(), "Notes/note.md");
+    }
+
+    #[wasm_bindgen_test]
+    fn an_external_url_is_extracted_but_never_rewritten() {
+        let content = "[docs](https://example.com/page.md)";
+        let links = extract_md_links(content);
+        assert_eq!(links[0].url(), "https://example.com/page.md");
+        assert_eq!(rewrite_md_links(content, "https://example.com/page.md", "elsewhere.md"), content);
+    }
+
+    #[wasm_bindgen_test]
+    fn rewrite_updates_a_matching_relative_link() {
+        let content = "See [note](Old/note.md) here.";
+        assert_eq!(rewrite_md_links(content, "Old/note.md", "New/note.md"), "See [note](New/note.md) here.");
+    }
+
+    #[wasm_bindgen_test]
+    fn rewrite_matches_url_encoded_spaces() {
+        let content = "![](Old%20Folder/img.png)";
+        assert_eq!(
+            rewrite_md_links(content, "Old Folder/img.png", "New Folder/img.png"),
+            "![](New%20Folder/img.png)"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn nested_headings_are_all_extracted_with_their_level_and_anchor() {
+        let content = "# Top\n\nSome intro.\n\n## Sub Section\n\nBody text.\n\n### Sub Sub Section\n";
+        let anchors = extract_anchors(content);
+        assert_eq!(anchors.headings.len(), 3);
+        assert_eq!(anchors.headings[0].level(), 1);
+        assert_eq!(anchors.headings[0].anchor(), "top");
+        assert_eq!(anchors.headings[1].level(), 2);
+        assert_eq!(anchors.headings[1].anchor(), "sub-section");
+        assert_eq!(anchors.headings[2].level(), 3);
+        assert_eq!(anchors.headings[2].anchor(), "sub-sub-section");
+    }
+
+    #[wasm_bindgen_test]
+    fn a_block_id_at_the_end_of_a_line_is_extracted() {
+        let content = "This is an important claim. ^important-claim\n\nUnrelated text.";
+        let anchors = extract_anchors(content);
+        assert_eq!(anchors.block_ids, vec!["important-claim".to_string()]);
+    }
+
+    #[wasm_bindgen_test]
+    fn duplicate_heading_text_is_disambiguated_consistently() {
+        let content = "# Overview\n\nFirst.\n\n# Overview\n\nSecond.\n\n# Overview\n\nThird.";
+        let anchors = extract_anchors(content);
+        let slugs: Vec<String> = anchors.headings.iter().map(|h| h.anchor()).collect();
+        assert_eq!(slugs, vec!["overview".to_string(), "overview-1".to_string(), "overview-2".to_string()]);
+    }
+
+    #[wasm_bindgen_test]
+    fn headings_and_block_ids_inside_a_fenced_code_block_are_ignored() {
+        let content = "# Real Heading\n```\n# Not A Heading\nfake line ^not-a-block\n```\nTrailing. ^real-block";
+        let anchors = extract_anchors(content);
+        assert_eq!(anchors.headings.len(), 1);
+        assert_eq!(anchors.headings[0].text(), "Real Heading");
+        assert_eq!(anchors.block_ids, vec!["real-block".to_string()]);
+    }
+
+    #[wasm_bindgen_test]
+    fn a_caret_in_the_middle_of_a_word_is_not_a_block_id() {
+        let content = "This has a caret^midword in it.";
+        let anchors = extract_anchors(content);
+        assert!(anchors.block_ids.is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn a_percent_sign_next_to_a_multi_byte_character_does_not_panic() {
+        let content = "![](%€uro.png)";
+        let links = extract_md_links(content);
+        assert_eq!(links[0].url(), "%€uro.png");
+        assert_eq!(rewrite_md_links(content, "%€uro.png", "renamed.png"), "![](renamed.png)");
+    }
+}
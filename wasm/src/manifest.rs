@@ -0,0 +1,359 @@
+//! Vault manifest: a snapshot of every file's path, content hash, size,
+//! and mtime, used to work out what changed between syncs.
+//!
+//! Serialization is deterministic — entries are always written out sorted
+//! by path — so two manifests built by adding the same entries in a
+//! different order still produce byte-identical JSON, which matters when
+//! the JSON is itself hashed or diffed for change detection.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::error::SyncError;
+use crate::hashing::content_hash;
+use crate::tombstone::TombstoneLog;
+
+/// The manifest JSON schema version written by [`Manifest::to_json`].
+///
+/// Bump this whenever the schema changes in a way [`migrate`] needs to
+/// know about, and add a case there translating the older shape forward.
+const MANIFEST_VERSION: u32 = 2;
+
+/// One vault entry: everything the sync planner needs to tell whether a
+/// file changed without re-reading its content.
+///
+/// `size` has `#[serde(default)]` so a version 1 manifest (predating this
+/// field) still deserializes, with missing sizes migrated to `0` by
+/// [`migrate`] rather than failing to parse.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Entry {
+    pub(crate) path: String,
+    pub(crate) hash: String,
+    #[serde(default)]
+    pub(crate) size: u64,
+    pub(crate) mtime: f64,
+}
+
+/// The current on-the-wire shape: a version tag alongside the entries,
+/// so [`Manifest::from_json`] knows which schema it's reading.
+#[derive(Serialize)]
+struct ManifestDocument<'a> {
+    version: u32,
+    entries: Vec<&'a Entry>,
+}
+
+#[derive(Deserialize)]
+struct RawManifestDocument {
+    version: u32,
+    entries: Vec<Entry>,
+}
+
+/// Parse `s` as a manifest of any version this crate understands, and
+/// return its entries migrated forward to the current schema.
+///
+/// Version 1 predates the `{version, entries}` envelope entirely: it's a
+/// bare JSON array of entries, some possibly missing `size` (defaulted to
+/// `0` via [`Entry`]'s `#[serde(default)]`). Any version newer than
+/// [`MANIFEST_VERSION`] is one this build doesn't know how to read yet,
+/// so it's rejected instead of silently misinterpreted.
+fn migrate(s: &str) -> Result<Vec<Entry>, SyncError> {
+    if let Ok(doc) = serde_json::from_str::<RawManifestDocument>(s) {
+        return if doc.version <= MANIFEST_VERSION {
+            Ok(doc.entries)
+        } else {
+            Err(SyncError::CorruptData(format!(
+                "unknown manifest version {} (this build understands up to {MANIFEST_VERSION})",
+                doc.version
+            )))
+        };
+    }
+
+    serde_json::from_str::<Vec<Entry>>(s).map_err(|err| SyncError::CorruptData(format!("invalid manifest JSON: {err}")))
+}
+
+/// A vault manifest: one [`Entry`] per file, keyed by path.
+///
+/// Rust's `&str` already guarantees every path handed to [`Manifest::add_entry`]
+/// is valid UTF-8, so [`Manifest::from_json`] is the only place invalid
+/// input can actually surface — it rejects malformed JSON with a clear
+/// error instead of panicking.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Manifest {
+    pub(crate) entries: BTreeMap<String, Entry>,
+}
+
+#[wasm_bindgen]
+impl Manifest {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Manifest {
+        Manifest::default()
+    }
+
+    /// Add or replace the entry for `path`.
+    pub fn add_entry(&mut self, path: &str, hash: &str, size: u64, mtime: f64) {
+        self.entries.insert(
+            path.to_string(),
+            Entry {
+                path: path.to_string(),
+                hash: hash.to_string(),
+                size,
+                mtime,
+            },
+        );
+    }
+
+    /// Serialize to JSON, tagged with [`MANIFEST_VERSION`] and with
+    /// entries sorted by path for deterministic output regardless of
+    /// insertion order.
+    pub fn to_json(&self) -> String {
+        let document = ManifestDocument {
+            version: MANIFEST_VERSION,
+            entries: self.entries.values().collect(),
+        };
+        serde_json::to_string(&document).expect("Entry serialization is infallible")
+    }
+
+    /// A single hash fingerprinting the entire manifest, so two devices
+    /// can compare one string instead of the whole manifest to tell
+    /// whether they're in sync. Hashes [`Manifest::to_json`]'s output, so
+    /// it inherits that method's determinism (insertion order doesn't
+    /// matter) and stays stable across crate versions as long as the JSON
+    /// format doesn't change; adding, removing, or modifying any entry
+    /// changes it. This is a cheaper top-level check than recomputing the
+    /// [`crate::merkle`] root, at the cost of not localizing which entry
+    /// differs.
+    pub fn fingerprint(&self) -> String {
+        content_hash(self.to_json().as_bytes())
+    }
+
+    /// Parse a manifest previously produced by [`Manifest::to_json`], at
+    /// any schema version this crate still understands (see [`migrate`]).
+    pub fn from_json(s: &str) -> Result<Manifest, SyncError> {
+        crate::guard(|| {
+            let entries = migrate(s)?;
+            let mut manifest = Manifest::default();
+            for entry in entries {
+                manifest.entries.insert(entry.path.clone(), entry);
+            }
+            Ok(manifest)
+        })?
+    }
+}
+
+/// Whichever of `a` and `b` has the newer `mtime`. Ties (equal `mtime`)
+/// are broken by content hash, greater hash winning — an arbitrary but
+/// deterministic total order, so two devices reconciling the same pair
+/// of entries always agree on the result instead of picking whichever
+/// happened to be passed as `a`.
+fn newer<'a>(a: &'a Entry, b: &'a Entry) -> &'a Entry {
+    match a.mtime.partial_cmp(&b.mtime) {
+        Some(std::cmp::Ordering::Greater) => a,
+        Some(std::cmp::Ordering::Less) => b,
+        _ if a.hash >= b.hash => a,
+        _ => b,
+    }
+}
+
+/// Merge `a` and `b` into a single reconciled manifest: for a path
+/// present in both, keep whichever entry has the newer `mtime` (see
+/// [`newer`] for the tie-break rule); for a path present in only one,
+/// keep that entry. Either way, drop the path entirely if `tombstones`
+/// shows it was deleted more recently than the surviving entry's
+/// `mtime`, so a device that already saw the deletion doesn't have it
+/// resurrected by a stale copy on the other side.
+#[wasm_bindgen]
+pub fn reconcile_manifests(a: &Manifest, b: &Manifest, tombstones: &TombstoneLog) -> Manifest {
+    let mut result = Manifest::default();
+    let paths: BTreeSet<&String> = a.entries.keys().chain(b.entries.keys()).collect();
+
+    for path in paths {
+        let entry = match (a.entries.get(path), b.entries.get(path)) {
+            (Some(entry_a), Some(entry_b)) => newer(entry_a, entry_b),
+            (Some(entry), None) | (None, Some(entry)) => entry,
+            (None, None) => unreachable!("path came from one of the two manifests"),
+        };
+
+        if !tombstones.is_deleted(path, entry.mtime) {
+            result.entries.insert(path.clone(), entry.clone());
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn json_output_is_sorted_by_path_regardless_of_insertion_order() {
+        let mut a = Manifest::new();
+        a.add_entry("b.md", "hash-b", 2, 2.0);
+        a.add_entry("a.md", "hash-a", 1, 1.0);
+
+        let mut b = Manifest::new();
+        b.add_entry("a.md", "hash-a", 1, 1.0);
+        b.add_entry("b.md", "hash-b", 2, 2.0);
+
+        assert_eq!(a.to_json(), b.to_json());
+        assert!(a.to_json().find("a.md").unwrap() < a.to_json().find("b.md").unwrap());
+    }
+
+    #[wasm_bindgen_test]
+    fn from_json_round_trips_to_json() {
+        let mut manifest = Manifest::new();
+        manifest.add_entry("notes/todo.md", "abc123", 42, 1_700_000_000.0);
+        manifest.add_entry("notes/journal.md", "def456", 7, 1_700_000_500.0);
+
+        let json = manifest.to_json();
+        let reparsed = Manifest::from_json(&json).unwrap();
+        assert_eq!(reparsed.to_json(), json);
+    }
+
+    #[wasm_bindgen_test]
+    fn adding_the_same_path_twice_replaces_the_entry() {
+        let mut manifest = Manifest::new();
+        manifest.add_entry("a.md", "old-hash", 1, 1.0);
+        manifest.add_entry("a.md", "new-hash", 2, 2.0);
+
+        assert_eq!(manifest.entries.len(), 1);
+        assert_eq!(manifest.entries["a.md"].hash, "new-hash");
+    }
+
+    #[wasm_bindgen_test]
+    fn fingerprint_is_stable_across_insertion_order() {
+        let mut a = Manifest::new();
+        a.add_entry("b.md", "hash-b", 2, 2.0);
+        a.add_entry("a.md", "hash-a", 1, 1.0);
+
+        let mut b = Manifest::new();
+        b.add_entry("a.md", "hash-a", 1, 1.0);
+        b.add_entry("b.md", "hash-b", 2, 2.0);
+
+        assert_eq!(a.fingerprint(), b.fingerprint());
+    }
+
+    #[wasm_bindgen_test]
+    fn fingerprint_changes_when_an_entry_is_added_removed_or_modified() {
+        let mut base = Manifest::new();
+        base.add_entry("a.md", "hash-a", 1, 1.0);
+        base.add_entry("b.md", "hash-b", 2, 2.0);
+        let base_fingerprint = base.fingerprint();
+
+        let mut added = base.clone();
+        added.add_entry("c.md", "hash-c", 3, 3.0);
+        assert_ne!(added.fingerprint(), base_fingerprint);
+
+        let mut modified = base.clone();
+        modified.add_entry("a.md", "hash-a-changed", 1, 1.0);
+        assert_ne!(modified.fingerprint(), base_fingerprint);
+
+        let mut removed = base.clone();
+        removed.entries.remove("a.md");
+        assert_ne!(removed.fingerprint(), base_fingerprint);
+    }
+
+    #[wasm_bindgen_test]
+    fn from_json_rejects_malformed_input_with_a_clear_error() {
+        let err = Manifest::from_json("not json").unwrap_err();
+        match err {
+            SyncError::CorruptData(message) => assert!(message.contains("invalid manifest JSON")),
+            other => panic!("expected SyncError::CorruptData, got {other:?}"),
+        }
+    }
+
+    /// A version 1 manifest: a bare array of entries, predating both the
+    /// `{version, entries}` envelope and the `size` field.
+    const V1_FIXTURE: &str =
+        r#"[{"path":"notes/todo.md","hash":"abc123","mtime":1700000000.0},{"path":"notes/journal.md","hash":"def456","mtime":1700000500.0}]"#;
+
+    #[wasm_bindgen_test]
+    fn a_v1_fixture_migrates_missing_size_to_zero_and_reserializes_as_current_version() {
+        let manifest = Manifest::from_json(V1_FIXTURE).unwrap();
+        assert_eq!(manifest.entries["notes/todo.md"].size, 0);
+        assert_eq!(manifest.entries["notes/journal.md"].size, 0);
+
+        let json = manifest.to_json();
+        let reparsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(reparsed["version"], MANIFEST_VERSION);
+        assert_eq!(Manifest::from_json(&json).unwrap(), manifest);
+    }
+
+    #[wasm_bindgen_test]
+    fn from_json_rejects_an_unknown_future_version() {
+        let future = format!(r#"{{"version":{},"entries":[]}}"#, MANIFEST_VERSION + 1);
+        let err = Manifest::from_json(&future).unwrap_err();
+        match err {
+            SyncError::CorruptData(message) => assert!(message.contains("unknown manifest version")),
+            other => panic!("expected SyncError::CorruptData, got {other:?}"),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn reconcile_picks_the_entry_with_the_newer_mtime() {
+        let mut a = Manifest::new();
+        a.add_entry("note.md", "hash-old", 1, 100.0);
+        let mut b = Manifest::new();
+        b.add_entry("note.md", "hash-new", 2, 200.0);
+
+        let result = reconcile_manifests(&a, &b, &TombstoneLog::new());
+        assert_eq!(result.entries["note.md"].hash, "hash-new");
+        assert_eq!(result.entries["note.md"].mtime, 200.0);
+    }
+
+    #[wasm_bindgen_test]
+    fn a_tombstone_newer_than_both_entries_suppresses_a_re_add() {
+        let mut a = Manifest::new();
+        a.add_entry("gone.md", "hash-a", 1, 100.0);
+        let mut b = Manifest::new();
+        b.add_entry("gone.md", "hash-b", 2, 150.0);
+
+        let mut tombstones = TombstoneLog::new();
+        tombstones.record("gone.md", 200.0);
+
+        let result = reconcile_manifests(&a, &b, &tombstones);
+        assert!(!result.entries.contains_key("gone.md"));
+    }
+
+    #[wasm_bindgen_test]
+    fn a_tombstone_older_than_the_surviving_entry_does_not_suppress_it() {
+        let mut a = Manifest::new();
+        a.add_entry("edited.md", "hash-a", 1, 300.0);
+        let b = Manifest::new();
+
+        let mut tombstones = TombstoneLog::new();
+        tombstones.record("edited.md", 200.0);
+
+        let result = reconcile_manifests(&a, &b, &tombstones);
+        assert_eq!(result.entries["edited.md"].hash, "hash-a");
+    }
+
+    #[wasm_bindgen_test]
+    fn a_timestamp_tie_is_broken_deterministically_by_the_greater_hash() {
+        let mut a = Manifest::new();
+        a.add_entry("note.md", "aaa", 1, 100.0);
+        let mut b = Manifest::new();
+        b.add_entry("note.md", "bbb", 2, 100.0);
+
+        let forward = reconcile_manifests(&a, &b, &TombstoneLog::new());
+        let backward = reconcile_manifests(&b, &a, &TombstoneLog::new());
+        assert_eq!(forward.entries["note.md"].hash, "bbb");
+        assert_eq!(backward.entries["note.md"].hash, "bbb");
+    }
+
+    #[wasm_bindgen_test]
+    fn a_path_present_in_only_one_manifest_is_kept() {
+        let mut a = Manifest::new();
+        a.add_entry("only-a.md", "hash-a", 1, 1.0);
+        let mut b = Manifest::new();
+        b.add_entry("only-b.md", "hash-b", 1, 1.0);
+
+        let result = reconcile_manifests(&a, &b, &TombstoneLog::new());
+        assert_eq!(result.entries["only-a.md"].hash, "hash-a");
+        assert_eq!(result.entries["only-b.md"].hash, "hash-b");
+    }
+}
@@ -0,0 +1,303 @@
+//! Diff two [`crate::manifest::Manifest`]s to drive a sync plan: which
+//! paths need uploading, downloading, or deleting.
+
+use wasm_bindgen::prelude::*;
+
+use crate::manifest::Manifest;
+use crate::tombstone::TombstoneLog;
+
+/// The result of comparing a local and remote [`Manifest`].
+///
+/// Path comparison is case-sensitive, so `Note.md` and `note.md` are
+/// treated as distinct entries — matching how most vault filesystems
+/// (and Obsidian's own path handling) behave.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ManifestDiff {
+    added: Vec<String>,
+    modified: Vec<String>,
+    deleted: Vec<String>,
+    unchanged: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl ManifestDiff {
+    /// Paths present in `remote` but not `local`.
+    #[wasm_bindgen(getter)]
+    pub fn added(&self) -> Vec<String> {
+        self.added.clone()
+    }
+
+    /// Paths present on both sides with different content hashes.
+    #[wasm_bindgen(getter)]
+    pub fn modified(&self) -> Vec<String> {
+        self.modified.clone()
+    }
+
+    /// Paths present in `local` but not `remote`.
+    #[wasm_bindgen(getter)]
+    pub fn deleted(&self) -> Vec<String> {
+        self.deleted.clone()
+    }
+
+    /// Paths present on both sides with the same content hash.
+    #[wasm_bindgen(getter)]
+    pub fn unchanged(&self) -> Vec<String> {
+        self.unchanged.clone()
+    }
+}
+
+/// Compare `local` and `remote`, classifying every path as added,
+/// modified, deleted, or unchanged. Each list is sorted by path.
+#[wasm_bindgen]
+pub fn diff_manifests(local: &Manifest, remote: &Manifest) -> ManifestDiff {
+    let mut diff = ManifestDiff::default();
+
+    for (path, local_entry) in &local.entries {
+        match remote.entries.get(path) {
+            Some(remote_entry) if remote_entry.hash == local_entry.hash => {
+                diff.unchanged.push(path.clone());
+            }
+            Some(_) => diff.modified.push(path.clone()),
+            None => diff.deleted.push(path.clone()),
+        }
+    }
+    for path in remote.entries.keys() {
+        if !local.entries.contains_key(path) {
+            diff.added.push(path.clone());
+        }
+    }
+
+    diff.added.sort();
+    diff.modified.sort();
+    diff.deleted.sort();
+    diff.unchanged.sort();
+    diff
+}
+
+/// A path that was renamed between `local` and `remote`, detected by
+/// [`detect_renames`] via matching content hashes.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rename {
+    from: String,
+    to: String,
+}
+
+#[wasm_bindgen]
+impl Rename {
+    #[wasm_bindgen(getter)]
+    pub fn from(&self) -> String {
+        self.from.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn to(&self) -> String {
+        self.to.clone()
+    }
+}
+
+/// Pair up `diff`'s deleted and added paths that share a content hash —
+/// the naive delete-plus-add a rename otherwise looks like — and remove
+/// matched entries from `diff.added`/`diff.deleted` in place, leaving
+/// only genuine adds and deletes behind.
+///
+/// A deleted path whose hash doesn't appear among the added paths (or
+/// vice versa) isn't a rename — most commonly because it was also
+/// edited, which changes its hash — and is left untouched.
+///
+/// If several deleted or added paths share the same hash, which pairing
+/// gets reported is otherwise arbitrary; this is made deterministic by
+/// walking both sides in sorted path order and greedily matching each
+/// deleted path against the earliest not-yet-matched added path with the
+/// same hash.
+#[wasm_bindgen]
+pub fn detect_renames(diff: &mut ManifestDiff, local: &Manifest, remote: &Manifest) -> Vec<Rename> {
+    let mut deleted = diff.deleted.clone();
+    deleted.sort();
+
+    let mut renames = Vec::new();
+    let mut matched_added = Vec::new();
+
+    for from in deleted {
+        let Some(local_entry) = local.entries.get(&from) else {
+            continue;
+        };
+        let to = diff
+            .added
+            .iter()
+            .filter(|path| !matched_added.contains(*path))
+            .filter(|path| remote.entries.get(*path).map(|entry| &entry.hash) == Some(&local_entry.hash))
+            .min()
+            .cloned();
+
+        if let Some(to) = to {
+            matched_added.push(to.clone());
+            renames.push(Rename { from: from.clone(), to });
+            diff.deleted.retain(|path| *path != from);
+        }
+    }
+    diff.added.retain(|path| !matched_added.contains(path));
+
+    renames.sort_by(|a, b| a.from.cmp(&b.from));
+    renames
+}
+
+/// Let a local deletion win over a remote copy that hasn't caught up to
+/// it yet: for every path in `diff.added`, if `tombstones` shows it was
+/// deleted locally more recently than `remote`'s copy was last modified,
+/// move it out of `added` and into `deleted` instead of downloading it
+/// back.
+///
+/// A tombstone older than the remote's mtime means the remote copy is a
+/// genuine edit made after the deletion, not a stale re-add — that path
+/// is left in `added` untouched.
+#[wasm_bindgen]
+pub fn apply_tombstones(diff: &mut ManifestDiff, tombstones: &TombstoneLog, remote: &Manifest) {
+    let reappeared: Vec<String> = diff
+        .added
+        .iter()
+        .filter(|path| {
+            remote
+                .entries
+                .get(*path)
+                .is_some_and(|entry| tombstones.is_deleted(path, entry.mtime))
+        })
+        .cloned()
+        .collect();
+
+    diff.added.retain(|path| !reappeared.contains(path));
+    diff.deleted.extend(reappeared);
+    diff.deleted.sort();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn manifest(entries: &[(&str, &str)]) -> Manifest {
+        let mut manifest = Manifest::new();
+        for (path, hash) in entries {
+            manifest.add_entry(path, hash, 0, 0.0);
+        }
+        manifest
+    }
+
+    #[wasm_bindgen_test]
+    fn detects_additions_and_deletions() {
+        let local = manifest(&[("a.md", "h1")]);
+        let remote = manifest(&[("b.md", "h2")]);
+        let diff = diff_manifests(&local, &remote);
+        assert_eq!(diff.added(), vec!["b.md"]);
+        assert_eq!(diff.deleted(), vec!["a.md"]);
+        assert!(diff.modified().is_empty());
+        assert!(diff.unchanged().is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn detects_modifications() {
+        let local = manifest(&[("a.md", "h1")]);
+        let remote = manifest(&[("a.md", "h2")]);
+        let diff = diff_manifests(&local, &remote);
+        assert_eq!(diff.modified(), vec!["a.md"]);
+        assert!(diff.added().is_empty());
+        assert!(diff.deleted().is_empty());
+        assert!(diff.unchanged().is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn no_change_puts_everything_in_unchanged() {
+        let local = manifest(&[("a.md", "h1"), ("b.md", "h2")]);
+        let remote = manifest(&[("a.md", "h1"), ("b.md", "h2")]);
+        let diff = diff_manifests(&local, &remote);
+        assert_eq!(diff.unchanged(), vec!["a.md", "b.md"]);
+        assert!(diff.added().is_empty());
+        assert!(diff.modified().is_empty());
+        assert!(diff.deleted().is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn paths_differing_only_by_case_are_distinct_entries() {
+        let local = manifest(&[("Note.md", "h1")]);
+        let remote = manifest(&[("note.md", "h1")]);
+        let diff = diff_manifests(&local, &remote);
+        assert_eq!(diff.added(), vec!["note.md"]);
+        assert_eq!(diff.deleted(), vec!["Note.md"]);
+    }
+
+    #[wasm_bindgen_test]
+    fn a_clean_rename_is_paired_and_removed_from_added_and_deleted() {
+        let local = manifest(&[("old.md", "h1")]);
+        let remote = manifest(&[("new.md", "h1")]);
+        let mut diff = diff_manifests(&local, &remote);
+
+        let renames = detect_renames(&mut diff, &local, &remote);
+        assert_eq!(renames.len(), 1);
+        assert_eq!(renames[0].from(), "old.md");
+        assert_eq!(renames[0].to(), "new.md");
+        assert!(diff.added().is_empty());
+        assert!(diff.deleted().is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn a_rename_plus_edit_is_not_detected_as_a_pure_rename() {
+        let local = manifest(&[("old.md", "h1")]);
+        let remote = manifest(&[("new.md", "h2")]);
+        let mut diff = diff_manifests(&local, &remote);
+
+        let renames = detect_renames(&mut diff, &local, &remote);
+        assert!(renames.is_empty());
+        assert_eq!(diff.added(), vec!["new.md"]);
+        assert_eq!(diff.deleted(), vec!["old.md"]);
+    }
+
+    #[wasm_bindgen_test]
+    fn ambiguous_duplicate_hashes_pair_deterministically_by_sorted_path() {
+        let local = manifest(&[("z.md", "h1"), ("y.md", "h1")]);
+        let remote = manifest(&[("b.md", "h1"), ("a.md", "h1")]);
+        let mut diff = diff_manifests(&local, &remote);
+
+        let renames = detect_renames(&mut diff, &local, &remote);
+        assert_eq!(renames.len(), 2);
+        // Deleted paths are matched in sorted order (y.md, z.md), each
+        // taking the earliest not-yet-matched added path (a.md, b.md).
+        assert_eq!(renames[0].from(), "y.md");
+        assert_eq!(renames[0].to(), "a.md");
+        assert_eq!(renames[1].from(), "z.md");
+        assert_eq!(renames[1].to(), "b.md");
+        assert!(diff.added().is_empty());
+        assert!(diff.deleted().is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn a_deletion_newer_than_the_remote_copy_wins_over_its_presence() {
+        let local = manifest(&[]);
+        let mut remote = Manifest::new();
+        remote.add_entry("gone.md", "h1", 0, 100.0);
+        let mut diff = diff_manifests(&local, &remote);
+        assert_eq!(diff.added(), vec!["gone.md"]);
+
+        let mut tombstones = TombstoneLog::new();
+        tombstones.record("gone.md", 200.0);
+        apply_tombstones(&mut diff, &tombstones, &remote);
+
+        assert!(diff.added().is_empty());
+        assert_eq!(diff.deleted(), vec!["gone.md"]);
+    }
+
+    #[wasm_bindgen_test]
+    fn a_deletion_older_than_the_remote_copy_does_not_suppress_a_genuine_edit() {
+        let local = manifest(&[]);
+        let mut remote = Manifest::new();
+        remote.add_entry("edited.md", "h2", 0, 300.0);
+        let mut diff = diff_manifests(&local, &remote);
+
+        let mut tombstones = TombstoneLog::new();
+        tombstones.record("edited.md", 200.0);
+        apply_tombstones(&mut diff, &tombstones, &remote);
+
+        assert_eq!(diff.added(), vec!["edited.md"]);
+        assert!(diff.deleted().is_empty());
+    }
+}
@@ -0,0 +1,243 @@
+//! Three-way merge for conflict-free sync.
+//!
+//! Given a common ancestor and two versions that diverged from it, merge
+//! non-overlapping changes automatically and only surface a conflict when
+//! both sides changed the same region differently.
+
+use wasm_bindgen::prelude::*;
+
+use crate::diff::{diff_lines, split_lines, DiffOp};
+
+/// A region where `local` and `remote` changed the same base content in
+/// different, irreconcilable ways.
+///
+/// `start`/`end` is the line range in [`MergeResult::merged_text`] where
+/// the conflicting content would go; the two sides' versions are kept
+/// separately in `local_text`/`remote_text` so a caller (e.g. conflict
+/// marker rendering) can splice them back in.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    start: u32,
+    end: u32,
+    local_text: String,
+    remote_text: String,
+}
+
+#[wasm_bindgen]
+impl Conflict {
+    #[wasm_bindgen(getter)]
+    pub fn start(&self) -> u32 {
+        self.start
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn end(&self) -> u32 {
+        self.end
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn local_text(&self) -> String {
+        self.local_text.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn remote_text(&self) -> String {
+        self.remote_text.clone()
+    }
+}
+
+/// The result of a [`merge3`] call.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeResult {
+    merged_text: String,
+    conflicts: Vec<Conflict>,
+}
+
+#[wasm_bindgen]
+impl MergeResult {
+    #[wasm_bindgen(getter)]
+    pub fn merged_text(&self) -> String {
+        self.merged_text.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        self.conflicts.clone()
+    }
+}
+
+/// A change hunk: the base-line range `[base_start, base_end)` was
+/// replaced with `new_lines`. `base_start == base_end` for a pure
+/// insertion; `new_lines` is empty for a pure deletion.
+struct Hunk<'a> {
+    base_start: usize,
+    base_end: usize,
+    new_lines: Vec<&'a str>,
+}
+
+/// Collapse a `diff_lines(base, other)` script into replace-style hunks
+/// anchored to base-line positions.
+fn hunks_from_ops<'a>(ops: &[DiffOp], other_lines: &'a [&'a str]) -> Vec<Hunk<'a>> {
+    let mut hunks = Vec::new();
+    let mut i = 0;
+    while i < ops.len() {
+        let take_insert = |op: &DiffOp| other_lines[op.new_start() as usize..op.new_end() as usize].to_vec();
+        match ops[i].kind().as_str() {
+            "equal" => i += 1,
+            "delete" => {
+                let base_start = ops[i].old_start() as usize;
+                let base_end = ops[i].old_end() as usize;
+                if let Some(next) = ops.get(i + 1).filter(|op| op.kind() == "insert") {
+                    hunks.push(Hunk {
+                        base_start,
+                        base_end,
+                        new_lines: take_insert(next),
+                    });
+                    i += 2;
+                } else {
+                    hunks.push(Hunk {
+                        base_start,
+                        base_end,
+                        new_lines: Vec::new(),
+                    });
+                    i += 1;
+                }
+            }
+            "insert" => {
+                if let Some(next) = ops.get(i + 1).filter(|op| op.kind() == "delete") {
+                    hunks.push(Hunk {
+                        base_start: next.old_start() as usize,
+                        base_end: next.old_end() as usize,
+                        new_lines: take_insert(&ops[i]),
+                    });
+                    i += 2;
+                } else {
+                    let pos = ops[i].old_start() as usize;
+                    hunks.push(Hunk {
+                        base_start: pos,
+                        base_end: pos,
+                        new_lines: take_insert(&ops[i]),
+                    });
+                    i += 1;
+                }
+            }
+            other => unreachable!("unexpected diff op kind {other}"),
+        }
+    }
+    hunks
+}
+
+/// Three-way merge `local` and `remote`, both derived from `base`.
+///
+/// Non-overlapping edits merge automatically. Overlapping edits merge
+/// cleanly only if they made the identical change; otherwise they're
+/// reported as a [`Conflict`].
+#[wasm_bindgen]
+pub fn merge3(base: &str, local: &str, remote: &str) -> MergeResult {
+    let base_lines = split_lines(base);
+    let local_lines = split_lines(local);
+    let remote_lines = split_lines(remote);
+
+    let local_hunks = hunks_from_ops(&diff_lines(base, local), &local_lines);
+    let remote_hunks = hunks_from_ops(&diff_lines(base, remote), &remote_lines);
+
+    let mut merged: Vec<String> = Vec::new();
+    let mut conflicts: Vec<Conflict> = Vec::new();
+    let (mut li, mut ri) = (0usize, 0usize);
+    let mut pos = 0usize;
+
+    while pos <= base_lines.len() {
+        let local_here = local_hunks.get(li).filter(|h| h.base_start == pos);
+        let remote_here = remote_hunks.get(ri).filter(|h| h.base_start == pos);
+
+        match (local_here, remote_here) {
+            (None, None) => {
+                if pos < base_lines.len() {
+                    merged.push(base_lines[pos].to_string());
+                }
+                pos += 1;
+            }
+            (Some(hunk), None) => {
+                merged.extend(hunk.new_lines.iter().map(|s| s.to_string()));
+                pos = pos.max(hunk.base_end);
+                li += 1;
+            }
+            (None, Some(hunk)) => {
+                merged.extend(hunk.new_lines.iter().map(|s| s.to_string()));
+                pos = pos.max(hunk.base_end);
+                ri += 1;
+            }
+            (Some(local_hunk), Some(remote_hunk)) => {
+                if local_hunk.base_end == remote_hunk.base_end && local_hunk.new_lines == remote_hunk.new_lines {
+                    merged.extend(local_hunk.new_lines.iter().map(|s| s.to_string()));
+                } else {
+                    let start = merged.len() as u32;
+                    conflicts.push(Conflict {
+                        start,
+                        end: start,
+                        local_text: local_hunk.new_lines.join("\n"),
+                        remote_text: remote_hunk.new_lines.join("\n"),
+                    });
+                }
+                pos = pos.max(local_hunk.base_end).max(remote_hunk.base_end);
+                li += 1;
+                ri += 1;
+            }
+        }
+    }
+
+    MergeResult {
+        merged_text: merged.join("\n"),
+        conflicts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn non_overlapping_edits_merge_cleanly() {
+        let base = "a\nb\nc\nd\ne";
+        let local = "A\nb\nc\nd\ne";
+        let remote = "a\nb\nc\nd\nE";
+        let result = merge3(base, local, remote);
+        assert!(result.conflicts().is_empty());
+        assert_eq!(result.merged_text(), "A\nb\nc\nd\nE");
+    }
+
+    #[wasm_bindgen_test]
+    fn overlapping_edits_produce_a_conflict() {
+        let base = "a\nb\nc";
+        let local = "a\nLOCAL\nc";
+        let remote = "a\nREMOTE\nc";
+        let result = merge3(base, local, remote);
+        assert_eq!(result.conflicts().len(), 1);
+        let conflict = &result.conflicts()[0];
+        assert_eq!(conflict.local_text(), "LOCAL");
+        assert_eq!(conflict.remote_text(), "REMOTE");
+    }
+
+    #[wasm_bindgen_test]
+    fn identical_change_on_both_sides_is_not_a_conflict() {
+        let base = "a\nb\nc";
+        let local = "a\nSAME\nc";
+        let remote = "a\nSAME\nc";
+        let result = merge3(base, local, remote);
+        assert!(result.conflicts().is_empty());
+        assert_eq!(result.merged_text(), "a\nSAME\nc");
+    }
+
+    #[wasm_bindgen_test]
+    fn base_equals_local_takes_remote() {
+        let base = "a\nb\nc";
+        let local = "a\nb\nc";
+        let remote = "a\nb\nc\nd";
+        let result = merge3(base, local, remote);
+        assert!(result.conflicts().is_empty());
+        assert_eq!(result.merged_text(), remote);
+    }
+}
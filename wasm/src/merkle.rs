@@ -0,0 +1,210 @@
+//! A Merkle tree over a [`Manifest`], so two devices can tell whether
+//! their vaults match with a single hash comparison instead of diffing
+//! every entry on every sync.
+//!
+//! The tree is built over entries in path order (which [`Manifest`]
+//! already guarantees, since it's keyed by path in a `BTreeMap`), so the
+//! same vault content always produces the same root hash regardless of
+//! which device or in what order the manifest was assembled.
+
+use std::cmp::Ordering;
+
+use wasm_bindgen::prelude::*;
+
+use crate::hashing::content_hash;
+use crate::manifest::{Entry, Manifest};
+
+fn leaf_hash(entry: &Entry) -> String {
+    content_hash(format!("{}\0{}", entry.path, entry.hash).as_bytes())
+}
+
+fn parent_hash(left: &str, right: &str) -> String {
+    content_hash(format!("{left}{right}").as_bytes())
+}
+
+/// A binary Merkle tree over a manifest's entries.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MerkleTree {
+    /// Leaf paths, sorted — parallel to `levels[0]`.
+    paths: Vec<String>,
+    /// `levels[0]` is one hash per entry; each following level pairs up
+    /// adjacent hashes from the level below (an unpaired trailing hash
+    /// is carried up unchanged) until only the root hash remains.
+    levels: Vec<Vec<String>>,
+}
+
+#[wasm_bindgen]
+impl MerkleTree {
+    /// Build a tree over `manifest`'s entries, sorted by path.
+    pub fn from_manifest(manifest: &Manifest) -> MerkleTree {
+        let paths: Vec<String> = manifest.entries.keys().cloned().collect();
+        let mut level: Vec<String> = if manifest.entries.is_empty() {
+            vec![content_hash(b"")]
+        } else {
+            manifest.entries.values().map(leaf_hash).collect()
+        };
+
+        let mut levels = vec![level.clone()];
+        while level.len() > 1 {
+            let mut next = Vec::with_capacity(level.len().div_ceil(2));
+            let mut i = 0;
+            while i < level.len() {
+                next.push(match level.get(i + 1) {
+                    Some(right) => parent_hash(&level[i], right),
+                    None => level[i].clone(),
+                });
+                i += 2;
+            }
+            levels.push(next.clone());
+            level = next;
+        }
+
+        MerkleTree { paths, levels }
+    }
+
+    /// The tree's root hash — identical on two devices only if their
+    /// vaults have exactly the same paths and content.
+    pub fn root_hash(&self) -> String {
+        let root_level = self.levels.last().expect("a tree always has at least one level");
+        root_level.first().cloned().expect("the root level always has exactly one hash")
+    }
+
+    /// Does `other_root` differ from this tree's root hash? `false`
+    /// means the vaults match and the sync can be skipped entirely.
+    pub fn diff_roots(&self, other_root: &str) -> bool {
+        self.root_hash() != other_root
+    }
+
+    /// Which paths differ between this tree and `other`? When both
+    /// trees cover the same set of paths, this skips every subtree
+    /// whose hash matches instead of comparing every leaf. When the
+    /// path sets themselves differ (files added or removed), it falls
+    /// back to a plain sorted merge of the two path lists.
+    pub fn differing_paths(&self, other: &MerkleTree) -> Vec<String> {
+        if self.paths != other.paths {
+            return merge_diff(&self.paths, &self.levels[0], &other.paths, &other.levels[0]);
+        }
+        let mut diffs = Vec::new();
+        if !self.paths.is_empty() {
+            collect_diffs(self, other, self.levels.len() - 1, 0, &mut diffs);
+        }
+        diffs
+    }
+}
+
+fn collect_diffs(a: &MerkleTree, b: &MerkleTree, level: usize, index: usize, out: &mut Vec<String>) {
+    if a.levels[level][index] == b.levels[level][index] {
+        return;
+    }
+    if level == 0 {
+        out.push(a.paths[index].clone());
+        return;
+    }
+    let left = index * 2;
+    collect_diffs(a, b, level - 1, left, out);
+    if left + 1 < a.levels[level - 1].len() {
+        collect_diffs(a, b, level - 1, left + 1, out);
+    }
+}
+
+/// Merge two sorted (path, leaf hash) lists, collecting every path that
+/// is missing from one side or whose hash differs on both.
+fn merge_diff(a_paths: &[String], a_hashes: &[String], b_paths: &[String], b_hashes: &[String]) -> Vec<String> {
+    let mut diffs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a_paths.len() && j < b_paths.len() {
+        match a_paths[i].cmp(&b_paths[j]) {
+            Ordering::Less => {
+                diffs.push(a_paths[i].clone());
+                i += 1;
+            }
+            Ordering::Greater => {
+                diffs.push(b_paths[j].clone());
+                j += 1;
+            }
+            Ordering::Equal => {
+                if a_hashes[i] != b_hashes[j] {
+                    diffs.push(a_paths[i].clone());
+                }
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    diffs.extend_from_slice(&a_paths[i..]);
+    diffs.extend_from_slice(&b_paths[j..]);
+    diffs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn manifest(entries: &[(&str, &str)]) -> Manifest {
+        let mut manifest = Manifest::new();
+        for (path, hash) in entries {
+            manifest.add_entry(path, hash, 1, 1.0);
+        }
+        manifest
+    }
+
+    #[wasm_bindgen_test]
+    fn identical_manifests_share_a_root() {
+        let a = manifest(&[("a.md", "h1"), ("b.md", "h2"), ("c.md", "h3")]);
+        let b = manifest(&[("c.md", "h3"), ("a.md", "h1"), ("b.md", "h2")]);
+        assert_eq!(MerkleTree::from_manifest(&a).root_hash(), MerkleTree::from_manifest(&b).root_hash());
+    }
+
+    #[wasm_bindgen_test]
+    fn a_single_changed_file_changes_the_root() {
+        let a = manifest(&[("a.md", "h1"), ("b.md", "h2")]);
+        let b = manifest(&[("a.md", "h1"), ("b.md", "h2-edited")]);
+        assert_ne!(MerkleTree::from_manifest(&a).root_hash(), MerkleTree::from_manifest(&b).root_hash());
+    }
+
+    #[wasm_bindgen_test]
+    fn diff_roots_reports_whether_the_quick_check_can_skip_the_sync() {
+        let a = MerkleTree::from_manifest(&manifest(&[("a.md", "h1")]));
+        let b = MerkleTree::from_manifest(&manifest(&[("a.md", "h1")]));
+        assert!(!a.diff_roots(&b.root_hash()));
+
+        let c = MerkleTree::from_manifest(&manifest(&[("a.md", "h1-edited")]));
+        assert!(a.diff_roots(&c.root_hash()));
+    }
+
+    #[wasm_bindgen_test]
+    fn differing_paths_finds_only_the_changed_file_in_a_large_manifest() {
+        let entries: Vec<(String, String)> =
+            (0..20).map(|i| (format!("note{i:02}.md"), format!("hash{i}"))).collect();
+        let mut a_manifest = Manifest::new();
+        let mut b_manifest = Manifest::new();
+        for (path, hash) in &entries {
+            a_manifest.add_entry(path, hash, 1, 1.0);
+            b_manifest.add_entry(path, hash, 1, 1.0);
+        }
+        b_manifest.add_entry("note05.md", "hash5-edited", 1, 1.0);
+
+        let a = MerkleTree::from_manifest(&a_manifest);
+        let b = MerkleTree::from_manifest(&b_manifest);
+        assert_eq!(a.differing_paths(&b), vec!["note05.md".to_string()]);
+    }
+
+    #[wasm_bindgen_test]
+    fn differing_paths_reports_added_and_removed_files() {
+        let a = MerkleTree::from_manifest(&manifest(&[("a.md", "h1"), ("b.md", "h2")]));
+        let b = MerkleTree::from_manifest(&manifest(&[("a.md", "h1"), ("c.md", "h3")]));
+        let mut diffs = a.differing_paths(&b);
+        diffs.sort();
+        assert_eq!(diffs, vec!["b.md".to_string(), "c.md".to_string()]);
+    }
+
+    #[wasm_bindgen_test]
+    fn empty_manifests_share_a_root_and_have_no_diffs() {
+        let a = MerkleTree::from_manifest(&Manifest::new());
+        let b = MerkleTree::from_manifest(&Manifest::new());
+        assert_eq!(a.root_hash(), b.root_hash());
+        assert!(a.differing_paths(&b).is_empty());
+    }
+}
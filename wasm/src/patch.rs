@@ -0,0 +1,378 @@
+//! A compact, unified-diff-like textual patch format for transmitting a
+//! note's edits instead of the whole file.
+//!
+//! Built on top of [`crate::diff::diff_lines`]: each hunk is a run of
+//! changed lines plus up to [`CONTEXT`] lines of unchanged context on
+//! either side, so [`apply_patch`] can confirm the base it's applying to
+//! still matches before touching anything. Nearby hunks within
+//! `2 * CONTEXT` lines of each other are merged into one, the same way
+//! `diff`/`patch` do it.
+//!
+//! Returns [`SyncError`] rather than the `JsValue` in the original
+//! request, matching every other fallible function in this crate.
+
+use wasm_bindgen::prelude::*;
+
+use crate::diff::{diff_lines, split_lines, DiffOp};
+use crate::error::SyncError;
+use crate::hashing::content_hash;
+
+/// Lines of context kept on either side of a change.
+const CONTEXT: usize = 3;
+
+struct Hunk {
+    old_start: usize,
+    old_len: usize,
+    new_start: usize,
+    new_len: usize,
+    lines: Vec<(char, String)>,
+}
+
+impl Hunk {
+    fn new(old_start: usize, new_start: usize) -> Hunk {
+        Hunk { old_start, new_start, old_len: 0, new_len: 0, lines: Vec::new() }
+    }
+
+    fn push(&mut self, tag: char, text: &str) {
+        match tag {
+            ' ' => {
+                self.old_len += 1;
+                self.new_len += 1;
+            }
+            '-' => self.old_len += 1,
+            '+' => self.new_len += 1,
+            _ => unreachable!("push is only ever called with ' ', '-', or '+'"),
+        }
+        self.lines.push((tag, text.to_string()));
+    }
+}
+
+/// Group `ops` into hunks, each with up to [`CONTEXT`] lines of leading
+/// and trailing context. A run of unchanged lines shorter than
+/// `2 * CONTEXT` is kept whole as connecting context between two
+/// changes rather than split, merging what would otherwise be two
+/// adjacent hunks into one.
+fn build_hunks(ops: &[DiffOp]) -> Vec<Hunk> {
+    let mut hunks = Vec::new();
+    let mut current: Option<Hunk> = None;
+    let mut pending_leading: Option<(usize, usize, &[String])> = None;
+    // A `DiffOp` always covers at least one line, so splitting its text on
+    // `\n` (unlike `split_lines` on a whole document) never needs the
+    // empty-string special case: an op for a single blank line has
+    // `text() == ""`, and `"".split('\n')` already yields one empty line.
+    let owned_lines: Vec<Vec<String>> =
+        ops.iter().map(|op| op.text().split('\n').map(str::to_string).collect()).collect();
+
+    let n = ops.len();
+    for (i, op) in ops.iter().enumerate() {
+        let lines: &[String] = &owned_lines[i];
+        let is_last_op = i == n - 1;
+
+        match op.kind().as_str() {
+            "equal" => {
+                if let Some(hunk) = current.as_mut() {
+                    if lines.len() <= 2 * CONTEXT || is_last_op {
+                        let take = if is_last_op { lines.len().min(CONTEXT) } else { lines.len() };
+                        for line in &lines[..take] {
+                            hunk.push(' ', line);
+                        }
+                        if is_last_op {
+                            hunks.push(current.take().unwrap());
+                        }
+                    } else {
+                        for line in &lines[..CONTEXT] {
+                            hunk.push(' ', line);
+                        }
+                        hunks.push(current.take().unwrap());
+                        let tail_old = op.old_end() as usize - CONTEXT;
+                        let tail_new = op.new_end() as usize - CONTEXT;
+                        pending_leading = Some((tail_old, tail_new, &lines[lines.len() - CONTEXT..]));
+                    }
+                } else if !is_last_op {
+                    let take = lines.len().min(CONTEXT);
+                    let start_old = op.old_end() as usize - take;
+                    let start_new = op.new_end() as usize - take;
+                    pending_leading = Some((start_old, start_new, &lines[lines.len() - take..]));
+                }
+            }
+            "delete" | "insert" => {
+                if current.is_none() {
+                    let (old_start, new_start, context) = pending_leading
+                        .take()
+                        .unwrap_or((op.old_start() as usize, op.new_start() as usize, &[]));
+                    let mut hunk = Hunk::new(old_start, new_start);
+                    for line in context {
+                        hunk.push(' ', line);
+                    }
+                    current = Some(hunk);
+                }
+                let hunk = current.as_mut().expect("just ensured a hunk is open");
+                let tag = if op.kind() == "delete" { '-' } else { '+' };
+                for line in lines {
+                    hunk.push(tag, line);
+                }
+            }
+            other => unreachable!("unexpected diff op kind {other}"),
+        }
+    }
+    if let Some(hunk) = current.take() {
+        hunks.push(hunk);
+    }
+    hunks
+}
+
+/// Diff `old` against `new` and render the result as a patch: each hunk
+/// is a `@@ -old_start,old_len +new_start,new_len @@` header (line
+/// numbers are 0-based) followed by its lines, each prefixed with `' '`
+/// (context), `'-'` (removed), or `'+'` (added). Identical inputs
+/// produce an empty patch.
+#[wasm_bindgen]
+pub fn make_patch(old: &str, new: &str) -> String {
+    let ops = diff_lines(old, new);
+    let hunks = build_hunks(&ops);
+    let mut out = String::new();
+    for hunk in &hunks {
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            hunk.old_start, hunk.old_len, hunk.new_start, hunk.new_len
+        ));
+        for (tag, text) in &hunk.lines {
+            out.push(*tag);
+            out.push_str(text);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+struct ParsedHunk<'a> {
+    old_start: usize,
+    lines: Vec<(char, &'a str)>,
+}
+
+fn parse_range(part: &str, line: &str) -> Result<usize, SyncError> {
+    part.split_once(',')
+        .and_then(|(start, _len)| start.parse().ok())
+        .ok_or_else(|| SyncError::PatchRejected(format!("malformed hunk header: {line:?}")))
+}
+
+fn parse_patch(patch: &str) -> Result<Vec<ParsedHunk<'_>>, SyncError> {
+    let mut hunks = Vec::new();
+    let mut current: Option<ParsedHunk> = None;
+
+    for line in split_lines(patch) {
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(body) = line.strip_prefix("@@ -").and_then(|s| s.strip_suffix(" @@")) {
+            if let Some(hunk) = current.take() {
+                hunks.push(hunk);
+            }
+            let (old_part, _new_part) = body
+                .split_once(" +")
+                .ok_or_else(|| SyncError::PatchRejected(format!("malformed hunk header: {line:?}")))?;
+            let old_start = parse_range(old_part, line)?;
+            current = Some(ParsedHunk { old_start, lines: Vec::new() });
+        } else {
+            let hunk = current
+                .as_mut()
+                .ok_or_else(|| SyncError::PatchRejected(format!("patch line outside of any hunk: {line:?}")))?;
+            let tag = line.chars().next().expect("empty lines were skipped above");
+            if !matches!(tag, ' ' | '-' | '+') {
+                return Err(SyncError::PatchRejected(format!("unrecognized patch line prefix {tag:?}")));
+            }
+            hunk.lines.push((tag, &line[tag.len_utf8()..]));
+        }
+    }
+    if let Some(hunk) = current.take() {
+        hunks.push(hunk);
+    }
+    Ok(hunks)
+}
+
+/// Apply `patch` (as produced by [`make_patch`]) to `old`.
+///
+/// Every context and removed line is checked against `old` before
+/// anything is written; a mismatch — `old` has diverged from the text
+/// the patch was generated against — returns
+/// [`SyncError::PatchRejected`] instead of silently producing a
+/// corrupted result.
+#[wasm_bindgen]
+pub fn apply_patch(old: &str, patch: &str) -> Result<String, SyncError> {
+    let old_lines = split_lines(old);
+    let hunks = parse_patch(patch)?;
+
+    let mut result: Vec<&str> = Vec::new();
+    let mut old_pos = 0usize;
+
+    for hunk in &hunks {
+        if hunk.old_start < old_pos || hunk.old_start > old_lines.len() {
+            return Err(SyncError::PatchRejected(format!(
+                "hunk at line {} does not fit the base text ({} lines already consumed of {})",
+                hunk.old_start,
+                old_pos,
+                old_lines.len()
+            )));
+        }
+        result.extend_from_slice(&old_lines[old_pos..hunk.old_start]);
+        old_pos = hunk.old_start;
+
+        for &(tag, text) in &hunk.lines {
+            match tag {
+                ' ' | '-' => {
+                    let Some(&actual) = old_lines.get(old_pos) else {
+                        return Err(SyncError::PatchRejected(format!(
+                            "patch expects a line at position {old_pos}, but the base only has {} lines",
+                            old_lines.len()
+                        )));
+                    };
+                    if actual != text {
+                        return Err(SyncError::PatchRejected(format!(
+                            "context mismatch at line {old_pos}: patch expects {text:?}, base has {actual:?}"
+                        )));
+                    }
+                    old_pos += 1;
+                    if tag == ' ' {
+                        result.push(text);
+                    }
+                }
+                '+' => result.push(text),
+                _ => unreachable!("parse_patch only ever stores ' ', '-', or '+'"),
+            }
+        }
+    }
+    result.extend_from_slice(&old_lines[old_pos..]);
+    Ok(result.join("\n"))
+}
+
+/// Check whether [`apply_patch`] would succeed on `old` and `patch`,
+/// without producing or discarding the result.
+///
+/// Runs the exact same parsing and context-matching logic as
+/// [`apply_patch`] — this isn't a separate, possibly-diverging
+/// implementation — so a caller can plan a whole batch of patches (queue
+/// the ones that won't cleanly apply for manual resolution) before
+/// mutating any files, and know the ones it does apply will succeed.
+#[wasm_bindgen]
+pub fn can_apply_patch(old: &str, patch: &str) -> bool {
+    apply_patch(old, patch).is_ok()
+}
+
+/// Apply `patch` to `old` as [`apply_patch`] does, then check the result's
+/// [`content_hash`] against `expected_hash` before returning it. Catches
+/// corruption a clean patch application wouldn't: a patch that applies
+/// without any context mismatch but still reconstructs the wrong bytes
+/// (a truncated transfer padded with garbage, a stray encoding
+/// conversion) fails loudly here instead of silently syncing bad data.
+#[wasm_bindgen]
+pub fn apply_patch_verified(old: &str, patch: &str, expected_hash: &str) -> Result<String, SyncError> {
+    let result = apply_patch(old, patch)?;
+    let actual_hash = content_hash(result.as_bytes());
+    if actual_hash != expected_hash {
+        return Err(SyncError::HashMismatch(format!(
+            "expected content hash {expected_hash}, got {actual_hash}"
+        )));
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn round_trips_a_change_in_the_middle_of_the_file() {
+        let old = "one\ntwo\nthree\nfour\nfive";
+        let new = "one\ntwo\nTHREE\nfour\nfive";
+        let patch = make_patch(old, new);
+        assert_eq!(apply_patch(old, &patch).unwrap(), new);
+    }
+
+    #[wasm_bindgen_test]
+    fn identical_inputs_produce_an_empty_patch_that_is_a_no_op() {
+        let text = "unchanged\ntext\nhere";
+        assert_eq!(make_patch(text, text), "");
+        assert_eq!(apply_patch(text, "").unwrap(), text);
+    }
+
+    #[wasm_bindgen_test]
+    fn a_hunk_at_the_very_start_of_the_file_round_trips() {
+        let old = "";
+        let new = "first line\nsecond line";
+        let patch = make_patch(old, new);
+        assert_eq!(apply_patch(old, &patch).unwrap(), new);
+    }
+
+    #[wasm_bindgen_test]
+    fn a_hunk_at_the_very_end_of_the_file_round_trips() {
+        let old = "keep one\nkeep two\nkeep three\nold last line";
+        let new = "keep one\nkeep two\nkeep three\nnew last line\nand one more";
+        let patch = make_patch(old, new);
+        assert_eq!(apply_patch(old, &patch).unwrap(), new);
+    }
+
+    #[wasm_bindgen_test]
+    fn two_changes_far_apart_produce_two_separate_hunks_and_still_round_trip() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\nn\no\np";
+        let new = "A\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\nn\no\nP";
+        let patch = make_patch(old, new);
+        let hunk_count = patch.lines().filter(|line| line.starts_with("@@ -")).count();
+        assert_eq!(hunk_count, 2, "expected two independent hunks: {patch}");
+        assert_eq!(apply_patch(old, &patch).unwrap(), new);
+    }
+
+    #[wasm_bindgen_test]
+    fn applying_a_patch_to_a_base_that_has_since_diverged_fails_cleanly() {
+        let old = "one\ntwo\nthree\nfour\nfive";
+        let new = "one\ntwo\nTHREE\nfour\nfive";
+        let patch = make_patch(old, new);
+
+        let diverged = "one\ntwo\nTWO-AND-A-HALF\nthree\nfour\nfive";
+        match apply_patch(diverged, &patch) {
+            Err(SyncError::PatchRejected(message)) => assert!(message.contains("context mismatch")),
+            other => panic!("expected Err(SyncError::PatchRejected(_)), got {other:?}"),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn can_apply_patch_agrees_with_apply_patch_on_a_clean_base() {
+        let old = "one\ntwo\nthree\nfour\nfive";
+        let new = "one\ntwo\nTHREE\nfour\nfive";
+        let patch = make_patch(old, new);
+        assert!(can_apply_patch(old, &patch));
+        assert!(apply_patch(old, &patch).is_ok());
+    }
+
+    #[wasm_bindgen_test]
+    fn can_apply_patch_agrees_with_apply_patch_on_a_diverged_base() {
+        let old = "one\ntwo\nthree\nfour\nfive";
+        let new = "one\ntwo\nTHREE\nfour\nfive";
+        let patch = make_patch(old, new);
+
+        let diverged = "one\ntwo\nTWO-AND-A-HALF\nthree\nfour\nfive";
+        assert!(!can_apply_patch(diverged, &patch));
+        assert!(apply_patch(diverged, &patch).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn apply_patch_verified_accepts_a_correct_expected_hash() {
+        let old = "one\ntwo\nthree";
+        let new = "one\nTWO\nthree";
+        let patch = make_patch(old, new);
+        let expected_hash = content_hash(new.as_bytes());
+        assert_eq!(apply_patch_verified(old, &patch, &expected_hash).unwrap(), new);
+    }
+
+    #[wasm_bindgen_test]
+    fn apply_patch_verified_rejects_a_wrong_expected_hash() {
+        let old = "one\ntwo\nthree";
+        let new = "one\nTWO\nthree";
+        let patch = make_patch(old, new);
+        let wrong_hash = content_hash(b"not the real result");
+        match apply_patch_verified(old, &patch, &wrong_hash) {
+            Err(SyncError::HashMismatch(message)) => assert!(message.contains(&wrong_hash)),
+            other => panic!("expected Err(SyncError::HashMismatch(_)), got {other:?}"),
+        }
+    }
+}
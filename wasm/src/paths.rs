@@ -0,0 +1,79 @@
+//! Path normalization so the same file always produces the same manifest
+//! key, regardless of which platform wrote it.
+//!
+//! macOS decomposes filenames into NFD Unicode (e.g. `é` as `e` + a
+//! combining acute accent) while everywhere else uses NFC (`é` as a
+//! single code point), so the exact same note gets two different byte
+//! sequences depending on which OS touched it. Re-composing to NFC
+//! preserves every accent and diacritic — it only changes which code
+//! points spell them out, never removes them.
+
+use unicode_normalization::UnicodeNormalization;
+use wasm_bindgen::prelude::*;
+
+/// Normalize `path` into a canonical manifest key: NFC-composed Unicode,
+/// duplicate `/` collapsed, a leading `./` stripped, and (if `case_fold`
+/// is set) lowercased for case-insensitive comparison.
+#[wasm_bindgen]
+pub fn normalize_path(path: &str, case_fold: bool) -> String {
+    let path = path.strip_prefix("./").unwrap_or(path);
+
+    let mut normalized = String::with_capacity(path.len());
+    let mut last_was_slash = false;
+    for c in path.chars() {
+        if c == '/' {
+            if last_was_slash {
+                continue;
+            }
+            last_was_slash = true;
+        } else {
+            last_was_slash = false;
+        }
+        normalized.push(c);
+    }
+
+    let normalized: String = normalized.nfc().collect();
+    if case_fold {
+        normalized.to_lowercase()
+    } else {
+        normalized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn nfd_and_nfc_input_normalize_to_the_same_output() {
+        let nfc = "Cafe\u{301}.md".nfc().collect::<String>(); // precomposed é
+        let nfd = "Cafe\u{0301}.md"; // e + combining acute accent
+        assert_eq!(normalize_path(nfd, false), normalize_path(&nfc, false));
+    }
+
+    #[wasm_bindgen_test]
+    fn preserves_accented_characters_rather_than_stripping_them() {
+        assert_eq!(normalize_path("Résumé.md", false), "Résumé.md");
+    }
+
+    #[wasm_bindgen_test]
+    fn case_fold_lowercases_the_whole_path() {
+        assert_eq!(normalize_path("Notes/TODO.md", true), "notes/todo.md");
+    }
+
+    #[wasm_bindgen_test]
+    fn case_fold_off_preserves_case() {
+        assert_eq!(normalize_path("Notes/TODO.md", false), "Notes/TODO.md");
+    }
+
+    #[wasm_bindgen_test]
+    fn collapses_duplicate_slashes() {
+        assert_eq!(normalize_path("notes//journal///today.md", false), "notes/journal/today.md");
+    }
+
+    #[wasm_bindgen_test]
+    fn strips_a_leading_dot_slash() {
+        assert_eq!(normalize_path("./notes/today.md", false), "notes/today.md");
+    }
+}
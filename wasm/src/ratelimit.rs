@@ -0,0 +1,137 @@
+//! Token-bucket rate limiting for throttling uploads, so sync stays a good
+//! citizen against remote APIs and a user's bandwidth cap instead of
+//! bursting as fast as the network allows.
+//!
+//! WASM has no ambient clock, so every method takes the current time
+//! explicitly (`now_ms`, milliseconds since any fixed epoch the caller
+//! chooses) instead of reading one itself.
+
+use wasm_bindgen::prelude::*;
+
+/// A token bucket: refills continuously at `refill_per_sec` tokens per
+/// second, capped at `capacity`, and drains as callers take tokens to
+/// spend their allowance.
+#[wasm_bindgen]
+pub struct TokenBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    tokens: f64,
+    last_refill_ms: f64,
+}
+
+#[wasm_bindgen]
+impl TokenBucket {
+    /// A bucket that starts full, with `capacity` tokens available
+    /// immediately.
+    #[wasm_bindgen(constructor)]
+    pub fn new(capacity: f64, refill_per_sec: f64) -> TokenBucket {
+        TokenBucket {
+            capacity,
+            refill_per_sec,
+            tokens: capacity,
+            last_refill_ms: 0.0,
+        }
+    }
+
+    /// Refill based on elapsed time since the last refill, without
+    /// exceeding `capacity`. `now_ms` moving backwards (e.g. a caller
+    /// passing an earlier timestamp by mistake) is treated as no time
+    /// having elapsed, rather than draining tokens.
+    fn refill(&mut self, now_ms: f64) {
+        let elapsed_ms = (now_ms - self.last_refill_ms).max(0.0);
+        let refilled = elapsed_ms / 1000.0 * self.refill_per_sec;
+        self.tokens = (self.tokens + refilled).min(self.capacity);
+        self.last_refill_ms = now_ms;
+    }
+
+    /// Attempt to spend `amount` tokens as of `now_ms`. Refills first, so
+    /// time that passed since the last call counts toward this attempt.
+    /// Returns `true` and deducts `amount` if enough tokens were
+    /// available, or `false` (leaving the bucket untouched) otherwise.
+    pub fn try_take(&mut self, now_ms: f64, amount: f64) -> bool {
+        self.refill(now_ms);
+        if self.tokens >= amount {
+            self.tokens -= amount;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// How many milliseconds from `now_ms` until `amount` tokens will be
+    /// available, so a caller can schedule its next attempt instead of
+    /// busy-polling. Returns `0.0` if `amount` is already available.
+    pub fn time_until_available(&mut self, now_ms: f64, amount: f64) -> f64 {
+        self.refill(now_ms);
+        let shortfall = amount - self.tokens;
+        if shortfall <= 0.0 {
+            return 0.0;
+        }
+        if self.refill_per_sec <= 0.0 {
+            return f64::INFINITY;
+        }
+        shortfall / self.refill_per_sec * 1000.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn a_fresh_bucket_starts_full_and_depletes_immediately() {
+        let mut bucket = TokenBucket::new(10.0, 1.0);
+        assert!(bucket.try_take(0.0, 10.0));
+        assert!(!bucket.try_take(0.0, 1.0));
+    }
+
+    #[wasm_bindgen_test]
+    fn tokens_refill_over_elapsed_time() {
+        let mut bucket = TokenBucket::new(10.0, 2.0);
+        assert!(bucket.try_take(0.0, 10.0));
+        assert!(!bucket.try_take(1_000.0, 3.0));
+        assert!(bucket.try_take(1_000.0, 2.0));
+    }
+
+    #[wasm_bindgen_test]
+    fn refill_never_exceeds_capacity() {
+        let mut bucket = TokenBucket::new(5.0, 100.0);
+        bucket.refill(0.0);
+        assert!(bucket.try_take(1_000_000.0, 5.0));
+        assert!(!bucket.try_take(1_000_000.0, 0.1));
+    }
+
+    #[wasm_bindgen_test]
+    fn fractional_refill_accumulates_across_multiple_calls() {
+        let mut bucket = TokenBucket::new(10.0, 1.0);
+        assert!(bucket.try_take(0.0, 10.0));
+        for _ in 0..10 {
+            bucket.try_take(0.0, 0.0);
+        }
+        assert!(!bucket.try_take(250.0, 0.26));
+        assert!(!bucket.try_take(500.0, 0.51));
+        assert!(bucket.try_take(1_000.0, 1.0));
+    }
+
+    #[wasm_bindgen_test]
+    fn time_until_available_is_zero_when_enough_tokens_already_exist() {
+        let mut bucket = TokenBucket::new(10.0, 1.0);
+        assert_eq!(bucket.time_until_available(0.0, 5.0), 0.0);
+    }
+
+    #[wasm_bindgen_test]
+    fn time_until_available_matches_the_refill_rate() {
+        let mut bucket = TokenBucket::new(10.0, 2.0);
+        assert!(bucket.try_take(0.0, 10.0));
+        assert_eq!(bucket.time_until_available(0.0, 4.0), 2_000.0);
+    }
+
+    #[wasm_bindgen_test]
+    fn waiting_the_reported_time_then_taking_succeeds() {
+        let mut bucket = TokenBucket::new(10.0, 3.0);
+        assert!(bucket.try_take(0.0, 10.0));
+        let wait = bucket.time_until_available(0.0, 6.0);
+        assert!(bucket.try_take(wait, 6.0));
+    }
+}
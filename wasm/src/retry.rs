@@ -0,0 +1,83 @@
+//! Exponential backoff scheduling for retrying network sync, as a pure
+//! function instead of ad hoc setTimeout logic scattered through
+//! TypeScript.
+
+use wasm_bindgen::prelude::*;
+
+/// Delay before the next retry attempt, in milliseconds.
+///
+/// Attempt `0` returns `base_ms`; each subsequent attempt doubles the
+/// delay, capped at `max_ms`. When `jitter` is `true`, the returned delay
+/// is drawn uniformly from `[0, capped]` ("full jitter") instead of
+/// `capped` exactly, so retrying clients don't all wake up in lockstep.
+/// `seed` drives that draw, so the result is deterministic and
+/// reproducible in tests instead of depending on a wall-clock RNG; pass a
+/// different seed per attempt (e.g. mixing in the attempt number) to avoid
+/// drawing the same jittered delay every time.
+#[wasm_bindgen]
+pub fn backoff_delay(attempt: u32, base_ms: u32, max_ms: u32, jitter: bool, seed: u64) -> u32 {
+    let growth = 2u64.checked_pow(attempt).unwrap_or(u64::MAX);
+    let capped = (base_ms as u64).saturating_mul(growth).min(max_ms as u64);
+
+    if !jitter || capped == 0 {
+        return capped as u32;
+    }
+
+    let mut state = seed;
+    state ^= state << 13;
+    state ^= state >> 7;
+    state ^= state << 17;
+    (state % (capped + 1)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn attempt_zero_returns_base_delay() {
+        assert_eq!(backoff_delay(0, 100, 10_000, false, 0), 100);
+    }
+
+    #[wasm_bindgen_test]
+    fn delay_doubles_with_each_attempt_until_capped() {
+        assert_eq!(backoff_delay(0, 100, 10_000, false, 0), 100);
+        assert_eq!(backoff_delay(1, 100, 10_000, false, 0), 200);
+        assert_eq!(backoff_delay(2, 100, 10_000, false, 0), 400);
+        assert_eq!(backoff_delay(3, 100, 10_000, false, 0), 800);
+    }
+
+    #[wasm_bindgen_test]
+    fn delay_never_exceeds_max_ms_even_for_large_attempts() {
+        for attempt in [10, 20, 32, 100, u32::MAX] {
+            assert_eq!(backoff_delay(attempt, 100, 10_000, false, 0), 10_000);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn jitter_never_exceeds_the_capped_delay() {
+        for attempt in 0..8 {
+            for seed in 0..20u64 {
+                let capped = backoff_delay(attempt, 50, 5_000, false, 0);
+                let jittered = backoff_delay(attempt, 50, 5_000, true, seed);
+                assert!(jittered <= capped, "attempt {attempt} seed {seed}: {jittered} > {capped}");
+            }
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn jitter_is_deterministic_for_a_fixed_seed() {
+        let a = backoff_delay(3, 100, 10_000, true, 42);
+        let b = backoff_delay(3, 100, 10_000, true, 42);
+        assert_eq!(a, b);
+    }
+
+    #[wasm_bindgen_test]
+    fn different_seeds_usually_produce_different_jitter() {
+        let distinct = (0..10u64)
+            .map(|seed| backoff_delay(4, 100, 10_000, true, seed))
+            .collect::<std::collections::HashSet<_>>();
+        assert!(distinct.len() > 1, "expected varying jitter across seeds, got {distinct:?}");
+    }
+}
@@ -0,0 +1,117 @@
+//! rsync-style weak rolling checksum for block-level delta transfer.
+//!
+//! Pairs with [`crate::hashing::content_hash`] to build the classic
+//! two-pass rsync algorithm on the TypeScript side: the weak checksum
+//! cheaply finds candidate matching blocks between a local and remote
+//! file, and the strong hash confirms a match before it's trusted.
+
+use wasm_bindgen::prelude::*;
+
+const MODULUS: u32 = 1 << 16;
+
+fn weak_checksum(block: &[u8]) -> (u32, u32) {
+    let len = block.len() as u32;
+    let mut a = 0u32;
+    let mut b = 0u32;
+    for (i, &byte) in block.iter().enumerate() {
+        a = (a + byte as u32) % MODULUS;
+        b = (b + (len - i as u32) * byte as u32) % MODULUS;
+    }
+    (a, b)
+}
+
+fn combine(a: u32, b: u32) -> u32 {
+    a + (b << 16)
+}
+
+/// One-shot weak checksum of `block`, for seeding a [`RollingHash`] or for
+/// independently re-checking a digest computed incrementally.
+#[wasm_bindgen]
+pub fn digest(block: &[u8]) -> u32 {
+    let (a, b) = weak_checksum(block);
+    combine(a, b)
+}
+
+/// An rsync-style weak checksum over a fixed-size sliding window, updated
+/// in O(1) per byte as the window slides forward.
+#[wasm_bindgen]
+pub struct RollingHash {
+    window: usize,
+    a: u32,
+    b: u32,
+}
+
+#[wasm_bindgen]
+impl RollingHash {
+    #[wasm_bindgen(constructor)]
+    pub fn new(window: usize) -> RollingHash {
+        RollingHash { window, a: 0, b: 0 }
+    }
+
+    /// Seed the checksum with the window's initial bytes. `initial.len()`
+    /// must equal the window size passed to [`RollingHash::new`].
+    pub fn reset(&mut self, initial: &[u8]) {
+        let (a, b) = weak_checksum(initial);
+        self.a = a;
+        self.b = b;
+    }
+
+    /// Slide the window forward by one byte: `exiting` leaves the window,
+    /// `entering` enters it. Returns the new digest.
+    pub fn roll(&mut self, exiting: u8, entering: u8) -> u32 {
+        let m = MODULUS as i64;
+        let window = self.window as i64;
+        let a = (self.a as i64 - exiting as i64 + entering as i64).rem_euclid(m) as u32;
+        let b = (self.b as i64 - window * exiting as i64 + a as i64).rem_euclid(m) as u32;
+        self.a = a;
+        self.b = b;
+        combine(self.a, self.b)
+    }
+
+    /// The current digest, without rolling.
+    pub fn value(&self) -> u32 {
+        combine(self.a, self.b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn pseudo_random_bytes(len: usize) -> Vec<u8> {
+        let mut state: u64 = 0xdead_beef_cafe_f00d;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state & 0xff) as u8
+            })
+            .collect()
+    }
+
+    #[wasm_bindgen_test]
+    fn reset_matches_one_shot_digest() {
+        let data = pseudo_random_bytes(16);
+        let mut rolling = RollingHash::new(8);
+        rolling.reset(&data[0..8]);
+        assert_eq!(rolling.value(), digest(&data[0..8]));
+    }
+
+    #[wasm_bindgen_test]
+    fn rolling_matches_fresh_digest_at_every_position() {
+        let data = pseudo_random_bytes(64);
+        let window = 8;
+        let mut rolling = RollingHash::new(window);
+        rolling.reset(&data[0..window]);
+        assert_eq!(rolling.value(), digest(&data[0..window]));
+
+        for start in 1..=(data.len() - window) {
+            let exiting = data[start - 1];
+            let entering = data[start + window - 1];
+            let rolled = rolling.roll(exiting, entering);
+            assert_eq!(rolled, digest(&data[start..start + window]));
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Typo-tolerant full-text search across the vault, so an in-app search
+//! box doesn't have to score thousands of notes in JS on every keystroke.
+//!
+//! Matching is subsequence-based (every query character must appear in
+//! the document in order, but not necessarily adjacent), the same style
+//! popularized by Sublime Text's "Goto Anything" and fzf: a typo or two
+//! doesn't stop a note from matching, it just scores lower than an exact
+//! or contiguous match.
+
+use wasm_bindgen::prelude::*;
+
+/// Bonus added when a matched character immediately follows the previous
+/// one, so a contiguous run of matches (as in an exact substring) scores
+/// higher than the same characters scattered across the document.
+const CONTIGUOUS_BONUS: f64 = 5.0;
+
+/// Bonus added for the position of the first matched character, so a
+/// match starting near the beginning of the document outranks an
+/// otherwise-identical match starting deep into it. Divided by
+/// `1 + position` so the bonus falls off quickly for early positions and
+/// flattens out for later ones.
+const EARLY_MATCH_BONUS: f64 = 10.0;
+
+/// One document's match against a [`fuzzy_search`] query: which document,
+/// how well it matched, and where, so the caller can highlight the
+/// matched characters without re-running the search in JS.
+#[wasm_bindgen(getter_with_clone)]
+pub struct SearchHit {
+    /// Index into the `documents` slice passed to [`fuzzy_search`].
+    pub document_index: u32,
+    /// Higher is a better match. Only meaningful relative to other hits
+    /// from the same search.
+    pub score: f64,
+    /// Char indices (not byte offsets) into the document that matched the
+    /// query, in order.
+    pub positions: Vec<u32>,
+}
+
+/// Match `query` against `document` as a case-insensitive subsequence,
+/// returning the char index of each matched character (greedily, always
+/// extending the previous match forward) or `None` if `query` isn't a
+/// subsequence of `document` at all.
+fn subsequence_match(query: &str, document: &str) -> Option<Vec<u32>> {
+    let query_lower: Vec<char> = query.chars().flat_map(|c| c.to_lowercase()).collect();
+    let doc_chars: Vec<char> = document.chars().collect();
+
+    let mut positions = Vec::with_capacity(query_lower.len());
+    let mut cursor = 0;
+    for &qc in &query_lower {
+        let found = doc_chars[cursor..]
+            .iter()
+            .position(|&dc| dc.to_lowercase().eq(qc.to_lowercase()))?;
+        let pos = cursor + found;
+        positions.push(pos as u32);
+        cursor = pos + 1;
+    }
+    Some(positions)
+}
+
+/// Score a set of matched `positions`, rewarding contiguous runs and an
+/// early first match (see [`CONTIGUOUS_BONUS`] and [`EARLY_MATCH_BONUS`]).
+fn score_positions(positions: &[u32]) -> f64 {
+    let mut score = 0.0;
+    let mut previous: Option<u32> = None;
+    for &pos in positions {
+        score += 1.0;
+        match previous {
+            Some(prev) if pos == prev + 1 => score += CONTIGUOUS_BONUS,
+            None => score += EARLY_MATCH_BONUS / (1.0 + pos as f64),
+            _ => {}
+        }
+        previous = Some(pos);
+    }
+    score
+}
+
+/// Fuzzy-search `documents` for `query`, returning up to `limit` hits
+/// sorted by descending score (ties broken by ascending document index,
+/// so results are stable across calls). An empty `query` matches nothing.
+#[wasm_bindgen]
+pub fn fuzzy_search(query: &str, documents: Vec<String>, limit: usize) -> Vec<SearchHit> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut hits: Vec<SearchHit> = documents
+        .iter()
+        .enumerate()
+        .filter_map(|(index, document)| {
+            let positions = subsequence_match(query, document)?;
+            let score = score_positions(&positions);
+            Some(SearchHit {
+                document_index: index as u32,
+                score,
+                positions,
+            })
+        })
+        .collect();
+
+    hits.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.document_index.cmp(&b.document_index))
+    });
+    hits.truncate(limit);
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn an_empty_query_returns_no_hits() {
+        let documents = vec!["anything".to_string(), "something".to_string()];
+        assert!(fuzzy_search("", documents, 10).is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn non_matching_documents_are_excluded() {
+        let documents = vec!["abc".to_string()];
+        assert!(fuzzy_search("xyz", documents, 10).is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn an_exact_match_ranks_above_a_scattered_subsequence_match() {
+        let documents = vec![
+            "this note is entirely about zebras".to_string(),
+            "z-index, why-bother, resize, another-affair, stuff".to_string(),
+        ];
+        let hits = fuzzy_search("zebra", documents, 10);
+
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].document_index, 0, "the contiguous exact match should rank first");
+        assert!(hits[0].score > hits[1].score);
+    }
+
+    #[wasm_bindgen_test]
+    fn a_typo_tolerant_subsequence_still_matches() {
+        // "meetign" (transposed letters) still matches "meeting" as a
+        // subsequence: m-e-e-t-i-g-n are all present in order... except
+        // the transposition breaks strict subsequence order, so instead
+        // exercise the classic dropped-letter typo, which subsequence
+        // matching tolerates directly.
+        let documents = vec!["quarterly planning meeting notes".to_string()];
+        let hits = fuzzy_search("meting", documents, 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].document_index, 0);
+    }
+
+    #[wasm_bindgen_test]
+    fn results_are_limited_and_sorted_descending_by_score() {
+        let documents = vec![
+            "aaa bbb".to_string(),
+            "abbb".to_string(),
+            "a......b......b......b".to_string(),
+        ];
+        let hits = fuzzy_search("abb", documents, 2);
+
+        assert_eq!(hits.len(), 2);
+        assert!(hits[0].score >= hits[1].score);
+    }
+
+    #[wasm_bindgen_test]
+    fn ties_break_by_ascending_document_index() {
+        let documents = vec!["xyz".to_string(), "xyz".to_string(), "xyz".to_string()];
+        let hits = fuzzy_search("xyz", documents, 10);
+
+        let indices: Vec<u32> = hits.iter().map(|hit| hit.document_index).collect();
+        assert_eq!(indices, vec![0, 1, 2]);
+    }
+
+    #[wasm_bindgen_test]
+    fn match_positions_locate_the_matched_characters_for_highlighting() {
+        let documents = vec!["hello world".to_string()];
+        let hits = fuzzy_search("hlo", documents, 10);
+
+        assert_eq!(hits.len(), 1);
+        // "h" at 0, "l" at 2 or 3, "o" after that.
+        assert_eq!(hits[0].positions[0], 0);
+        assert!(hits[0].positions.windows(2).all(|w| w[0] < w[1]));
+    }
+}
@@ -0,0 +1,163 @@
+//! A sync session's phase, as an explicit state machine.
+//!
+//! The sync flow has distinct phases — `Idle -> Scanning -> Diffing ->
+//! Transferring -> Merging -> Done`, with `Failed` reachable from any of
+//! them — and tracking that as loose booleans on the TypeScript side lets
+//! them drift out of sync (e.g. nothing stops "transferring" from being
+//! set before "diffing" finished). Centralizing the transitions here
+//! makes an illegal jump a rejected [`SyncError::BadTransition`] instead
+//! of silent inconsistent state.
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::SyncError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum SessionState {
+    #[default]
+    Idle,
+    Scanning,
+    Diffing,
+    Transferring,
+    Merging,
+    Done,
+    Failed,
+}
+
+impl SessionState {
+    fn as_str(self) -> &'static str {
+        match self {
+            SessionState::Idle => "idle",
+            SessionState::Scanning => "scanning",
+            SessionState::Diffing => "diffing",
+            SessionState::Transferring => "transferring",
+            SessionState::Merging => "merging",
+            SessionState::Done => "done",
+            SessionState::Failed => "failed",
+        }
+    }
+}
+
+/// A sync session, tracking which phase it's in and rejecting illegal
+/// jumps between them.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct SyncSession {
+    state: SessionState,
+}
+
+#[wasm_bindgen]
+impl SyncSession {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> SyncSession {
+        SyncSession::default()
+    }
+
+    /// The session's current phase, as a lowercase string.
+    #[wasm_bindgen(getter)]
+    pub fn state(&self) -> String {
+        self.state.as_str().to_string()
+    }
+
+    pub fn start_scanning(&mut self) -> Result<(), SyncError> {
+        self.transition(SessionState::Idle, SessionState::Scanning)
+    }
+
+    pub fn start_diffing(&mut self) -> Result<(), SyncError> {
+        self.transition(SessionState::Scanning, SessionState::Diffing)
+    }
+
+    pub fn start_transferring(&mut self) -> Result<(), SyncError> {
+        self.transition(SessionState::Diffing, SessionState::Transferring)
+    }
+
+    pub fn start_merging(&mut self) -> Result<(), SyncError> {
+        self.transition(SessionState::Transferring, SessionState::Merging)
+    }
+
+    pub fn finish(&mut self) -> Result<(), SyncError> {
+        self.transition(SessionState::Merging, SessionState::Done)
+    }
+
+    /// Move to the failed phase. Unlike the other transitions, this is
+    /// legal from any state: a sync can fail at any phase, and there's
+    /// no invariant left to protect once it has.
+    pub fn fail(&mut self) {
+        self.state = SessionState::Failed;
+    }
+
+    fn transition(&mut self, expected: SessionState, next: SessionState) -> Result<(), SyncError> {
+        if self.state != expected {
+            return Err(SyncError::BadTransition(format!(
+                "cannot move to {} from {}, expected to be in {}",
+                next.as_str(),
+                self.state.as_str(),
+                expected.as_str()
+            )));
+        }
+        self.state = next;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn a_valid_full_cycle_reaches_done() {
+        let mut session = SyncSession::new();
+        assert_eq!(session.state(), "idle");
+
+        session.start_scanning().unwrap();
+        assert_eq!(session.state(), "scanning");
+
+        session.start_diffing().unwrap();
+        assert_eq!(session.state(), "diffing");
+
+        session.start_transferring().unwrap();
+        assert_eq!(session.state(), "transferring");
+
+        session.start_merging().unwrap();
+        assert_eq!(session.state(), "merging");
+
+        session.finish().unwrap();
+        assert_eq!(session.state(), "done");
+    }
+
+    #[wasm_bindgen_test]
+    fn an_illegal_jump_is_rejected_and_leaves_state_unchanged() {
+        let mut session = SyncSession::new();
+        let err = session.start_transferring().unwrap_err();
+        match err {
+            SyncError::BadTransition(message) => assert!(message.contains("idle")),
+            other => panic!("expected Err(SyncError::BadTransition(_)), got {other:?}"),
+        }
+        assert_eq!(session.state(), "idle");
+    }
+
+    #[wasm_bindgen_test]
+    fn fail_is_legal_from_any_state() {
+        for setup in [
+            |_s: &mut SyncSession| {},
+            |s: &mut SyncSession| s.start_scanning().unwrap(),
+            |s: &mut SyncSession| {
+                s.start_scanning().unwrap();
+                s.start_diffing().unwrap();
+            },
+            |s: &mut SyncSession| {
+                s.start_scanning().unwrap();
+                s.start_diffing().unwrap();
+                s.start_transferring().unwrap();
+                s.start_merging().unwrap();
+                s.finish().unwrap();
+            },
+        ] {
+            let mut session = SyncSession::new();
+            setup(&mut session);
+            session.fail();
+            assert_eq!(session.state(), "failed");
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! Fuzzy similarity scoring for rename detection when a file was renamed
+//! *and* lightly edited, so its content hash no longer matches exactly
+//! (see [`crate::manifest_diff::detect_renames`] for the exact-hash case).
+//!
+//! Comparing whole files character-by-character is O(n²) and pointless
+//! for large attachments, so instead we reuse [`crate::chunking`]'s
+//! content-defined chunks: two texts are similar if they share many of
+//! the same chunk hashes, however their bytes happen to be split. This
+//! scales with content length, not its square, and — being anchored to
+//! content rather than position — degrades gracefully under the same
+//! insertions/deletions that content-defined chunking already tolerates.
+
+use std::collections::HashSet;
+
+use wasm_bindgen::prelude::*;
+
+use crate::chunking::chunk_content;
+use crate::hashing::content_hash;
+
+const MIN_CHUNK: usize = 16;
+const AVG_CHUNK: usize = 64;
+const MAX_CHUNK: usize = 256;
+
+fn chunk_hashes(text: &str) -> HashSet<String> {
+    chunk_content(text.as_bytes(), MIN_CHUNK, AVG_CHUNK, MAX_CHUNK)
+        .expect("MIN_CHUNK <= AVG_CHUNK <= MAX_CHUNK are fixed, valid bounds")
+        .into_iter()
+        .map(|chunk| chunk.hash())
+        .collect()
+}
+
+/// Jaccard similarity of `a` and `b`'s content-defined chunk sets: the
+/// fraction of their combined distinct chunks that are shared by both.
+/// Symmetric, `1.0` for identical input, `0.0` for input sharing no
+/// chunks at all (including when both are empty, since there's nothing
+/// in common to claim similarity from).
+#[wasm_bindgen]
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let a = chunk_hashes(a);
+    let b = chunk_hashes(b);
+
+    if a.is_empty() && b.is_empty() {
+        return 0.0;
+    }
+
+    let intersection = a.intersection(&b).count();
+    let union = a.union(&b).count();
+    intersection as f64 / union as f64
+}
+
+/// Hash `token` under permutation `seed`, by mixing `seed` into the
+/// bytes before hashing rather than hashing `token` alone, so the
+/// `num_hashes` permutations [`minhash`] takes the minimum over are
+/// independent instead of all agreeing on the same minimal token.
+fn permuted_hash(token: &str, seed: u32) -> u32 {
+    let mut tagged = Vec::with_capacity(4 + token.len());
+    tagged.extend_from_slice(&seed.to_le_bytes());
+    tagged.extend_from_slice(token.as_bytes());
+    let digest = content_hash(&tagged);
+    u32::from_str_radix(&digest[..8], 16).expect("hex digest prefix is always 8 valid hex digits")
+}
+
+/// A MinHash signature for `text`'s set of whitespace-separated tokens:
+/// `num_hashes` independent minimums, one per permutation, so two
+/// signatures can be compared in `O(num_hashes)` regardless of how long
+/// the original documents were (see [`estimate_similarity`]).
+///
+/// This scales to vault-wide near-duplicate detection where comparing
+/// every pair of notes with [`similarity`] directly would be quadratic
+/// in the number of notes.
+///
+/// Empty (or whitespace-only) `text` has no tokens to permute, so this
+/// returns an empty signature rather than `num_hashes` copies of
+/// `u32::MAX` — which would otherwise make [`estimate_similarity`] call
+/// two empty documents identical, contradicting [`similarity`]'s
+/// documented `0.0` for that case.
+#[wasm_bindgen]
+pub fn minhash(text: &str, num_hashes: usize) -> Vec<u32> {
+    let tokens: HashSet<&str> = text.split_whitespace().collect();
+    if tokens.is_empty() {
+        return Vec::new();
+    }
+    (0..num_hashes as u32)
+        .map(|seed| tokens.iter().map(|token| permuted_hash(token, seed)).min().unwrap_or(u32::MAX))
+        .collect()
+}
+
+/// Estimate the Jaccard similarity of the two documents [`minhash`]
+/// produced `sig_a` and `sig_b` from, as the fraction of positions where
+/// the two signatures agree. The estimate's accuracy improves with
+/// `num_hashes`; both signatures must have been built with the same
+/// `num_hashes` to be comparable. If either is empty, similarity is
+/// reported as `0.0` rather than dividing by zero.
+#[wasm_bindgen]
+pub fn estimate_similarity(sig_a: &[u32], sig_b: &[u32]) -> f64 {
+    let compared = sig_a.len().min(sig_b.len());
+    if compared == 0 {
+        return 0.0;
+    }
+
+    let agreeing = sig_a.iter().zip(sig_b.iter()).filter(|(a, b)| a == b).count();
+    agreeing as f64 / compared as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    /// Non-repeating pseudo-random ASCII text, so chunk boundaries and
+    /// hashes don't collapse onto a handful of distinct values the way a
+    /// short repeated phrase would.
+    fn pseudo_random_text(len: usize, seed: u64) -> String {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (b' ' + (state % 95) as u8) as char
+            })
+            .collect()
+    }
+
+    #[wasm_bindgen_test]
+    fn identical_input_scores_one() {
+        let text = pseudo_random_text(5000, 1);
+        assert_eq!(similarity(&text, &text), 1.0);
+    }
+
+    #[wasm_bindgen_test]
+    fn totally_different_input_scores_zero() {
+        let a = pseudo_random_text(5000, 1);
+        let b = pseudo_random_text(5000, 2);
+        assert_eq!(similarity(&a, &b), 0.0);
+    }
+
+    #[wasm_bindgen_test]
+    fn mostly_shared_content_scores_highly() {
+        let a = pseudo_random_text(20_000, 3);
+        let mut b = a.clone();
+        let cut = b.len() / 2;
+        b.replace_range(cut..cut + 20, "SOMETHING TOTALLY DIFFERENT HERE!!!");
+
+        let score = similarity(&a, &b);
+        assert!(score > 0.9, "expected a highly similar score, got {score}");
+        assert!(score < 1.0, "expected the edit to be visible, got {score}");
+    }
+
+    #[wasm_bindgen_test]
+    fn similarity_is_symmetric() {
+        let a = pseudo_random_text(5000, 4);
+        let mut b = a.clone();
+        b.push_str("a small tail that only b has");
+        assert_eq!(similarity(&a, &b), similarity(&b, &a));
+    }
+
+    /// A document made of distinct, individually-numbered tokens
+    /// `word{start}` through `word{end - 1}`, so two documents built from
+    /// overlapping ranges have an exactly known true Jaccard similarity
+    /// to compare [`estimate_similarity`] against.
+    fn word_range(start: usize, end: usize) -> String {
+        (start..end).map(|i| format!("word{i}")).collect::<Vec<_>>().join(" ")
+    }
+
+    fn true_jaccard(a: &str, b: &str) -> f64 {
+        let a: HashSet<&str> = a.split_whitespace().collect();
+        let b: HashSet<&str> = b.split_whitespace().collect();
+        a.intersection(&b).count() as f64 / a.union(&b).count() as f64
+    }
+
+    #[wasm_bindgen_test]
+    fn identical_documents_estimate_full_similarity() {
+        let text = word_range(0, 200);
+        let sig = minhash(&text, 128);
+        assert_eq!(estimate_similarity(&sig, &sig), 1.0);
+    }
+
+    #[wasm_bindgen_test]
+    fn disjoint_documents_estimate_near_zero_similarity() {
+        let a = minhash(&word_range(0, 200), 200);
+        let b = minhash(&word_range(200, 400), 200);
+        let score = estimate_similarity(&a, &b);
+        assert!(score < 0.15, "expected disjoint documents to estimate near 0.0, got {score}");
+    }
+
+    #[wasm_bindgen_test]
+    fn empty_documents_estimate_zero_similarity_not_one() {
+        let sig_a = minhash("", 128);
+        let sig_b = minhash("   ", 128);
+        assert!(sig_a.is_empty());
+        assert!(sig_b.is_empty());
+        assert_eq!(estimate_similarity(&sig_a, &sig_b), 0.0);
+    }
+
+    #[wasm_bindgen_test]
+    fn the_estimate_is_within_tolerance_of_the_true_jaccard_similarity() {
+        let text_a = word_range(0, 100);
+        let text_b = word_range(50, 150);
+        let expected = true_jaccard(&text_a, &text_b);
+
+        let sig_a = minhash(&text_a, 200);
+        let sig_b = minhash(&text_b, 200);
+        let estimate = estimate_similarity(&sig_a, &sig_b);
+
+        assert!(
+            (estimate - expected).abs() < 0.15,
+            "expected an estimate near the true Jaccard similarity {expected}, got {estimate}"
+        );
+    }
+}
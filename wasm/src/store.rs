@@ -0,0 +1,100 @@
+//! A content-addressed store of chunk bytes keyed by their
+//! [`crate::hashing::content_hash`], so a delta sync can ask "do I
+//! already have this chunk?" before uploading or downloading it.
+//!
+//! Pairs with [`crate::chunking::chunk_content`]: split a file into
+//! chunks, look up each chunk's hash here, and only transfer the ones
+//! [`ChunkStore::has`] doesn't already know about.
+
+use std::collections::BTreeMap;
+
+use wasm_bindgen::prelude::*;
+
+/// A content-addressed chunk store, keyed by hash.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct ChunkStore {
+    chunks: BTreeMap<String, Vec<u8>>,
+}
+
+#[wasm_bindgen]
+impl ChunkStore {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> ChunkStore {
+        ChunkStore::default()
+    }
+
+    /// Store `data` under `hash`. Inserting the same hash again is a
+    /// no-op: content-addressed data is immutable by definition, so the
+    /// existing copy is kept rather than duplicated.
+    pub fn insert(&mut self, hash: &str, data: &[u8]) {
+        self.chunks.entry(hash.to_string()).or_insert_with(|| data.to_vec());
+    }
+
+    /// Whether a chunk with this hash is already present.
+    pub fn has(&self, hash: &str) -> bool {
+        self.chunks.contains_key(hash)
+    }
+
+    /// The chunk's bytes, or `None` if it isn't present.
+    pub fn get(&self, hash: &str) -> Option<Vec<u8>> {
+        self.chunks.get(hash).cloned()
+    }
+
+    /// The number of distinct chunks currently stored.
+    pub fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Whether the store holds no chunks.
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn insert_then_has_and_get_round_trip() {
+        let mut store = ChunkStore::new();
+        assert!(!store.has("abc"));
+        assert_eq!(store.get("abc"), None);
+
+        store.insert("abc", b"hello");
+        assert!(store.has("abc"));
+        assert_eq!(store.get("abc"), Some(b"hello".to_vec()));
+        assert_eq!(store.len(), 1);
+    }
+
+    #[wasm_bindgen_test]
+    fn get_on_a_missing_hash_is_none() {
+        let store = ChunkStore::new();
+        assert_eq!(store.get("nope"), None);
+    }
+
+    #[wasm_bindgen_test]
+    fn re_inserting_the_same_hash_is_idempotent() {
+        let mut store = ChunkStore::new();
+        store.insert("abc", b"hello");
+        store.insert("abc", b"hello");
+        store.insert("abc", b"a different payload under the same hash");
+
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.get("abc"), Some(b"hello".to_vec()));
+    }
+
+    #[wasm_bindgen_test]
+    fn len_and_is_empty_track_distinct_hashes() {
+        let mut store = ChunkStore::new();
+        assert!(store.is_empty());
+
+        store.insert("a", b"1");
+        store.insert("b", b"2");
+        store.insert("a", b"1");
+        assert_eq!(store.len(), 2);
+        assert!(!store.is_empty());
+    }
+}
@@ -0,0 +1,365 @@
+//! Line-ending normalization, so the same note edited on Windows (CRLF)
+//! and elsewhere (LF) doesn't hash as two different files and trigger a
+//! phantom conflict.
+//!
+//! Every function here treats `\r\n`, `\n`, and a lone `\r` (the old
+//! classic-Mac convention some tools still emit) as a single line break,
+//! so mixed or unusual line endings are handled the same way regardless
+//! of source.
+
+use wasm_bindgen::prelude::*;
+
+/// Split `text` into lines on any of `\r\n`, `\n`, or a lone `\r`,
+/// without the line-ending characters themselves.
+fn split_lines(text: &str) -> Vec<&str> {
+    let mut lines = Vec::new();
+    let bytes = text.as_bytes();
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\r' => {
+                lines.push(&text[start..i]);
+                i += if bytes.get(i + 1) == Some(&b'\n') { 2 } else { 1 };
+                start = i;
+            }
+            b'\n' => {
+                lines.push(&text[start..i]);
+                i += 1;
+                start = i;
+            }
+            _ => i += 1,
+        }
+    }
+    lines.push(&text[start..]);
+    lines
+}
+
+/// Join `text`'s lines back together with `separator`, preserving
+/// whether the original text ended with a trailing line break.
+fn join_lines(text: &str, separator: &str) -> String {
+    let ends_with_break = matches!(text.as_bytes().last(), Some(b'\n') | Some(b'\r'));
+    let mut lines = split_lines(text);
+    if ends_with_break {
+        lines.pop(); // split_lines' trailing empty string after the last break
+    }
+    let mut joined = lines.join(separator);
+    if ends_with_break {
+        joined.push_str(separator);
+    }
+    joined
+}
+
+/// Convert `text` to use `style` line endings: `"lf"`, `"crlf"`, or
+/// `"auto"` (keep whichever of LF/CRLF is more common in `text`, so a
+/// file isn't needlessly rewritten wholesale for one stray line).
+/// An unrecognized style falls back to `"lf"`.
+#[wasm_bindgen]
+pub fn normalize_line_endings(text: &str, style: &str) -> String {
+    let separator = match style {
+        "crlf" => "\r\n",
+        "auto" => {
+            let crlf_count = text.matches("\r\n").count();
+            let lf_only_count = text.matches('\n').count() - crlf_count;
+            if crlf_count > lf_only_count { "\r\n" } else { "\n" }
+        }
+        _ => "\n",
+    };
+    join_lines(text, separator)
+}
+
+/// Always normalize to LF, for a stable hash independent of whichever
+/// platform last saved the file.
+#[wasm_bindgen]
+pub fn normalize_for_hash(text: &str) -> String {
+    normalize_line_endings(text, "lf")
+}
+
+/// The number of leading bytes sniffed for binary content; large enough
+/// to catch the header of the file formats we care about, small enough
+/// to stay cheap on multi-megabyte attachments.
+const SNIFF_LEN: usize = 8000;
+
+/// Is `data` probably text (and so worth diffing/merging line-by-line),
+/// as opposed to binary (which should go through [`crate::bindelta`]
+/// instead)? A file is text if it's empty, contains no null byte in its
+/// first [`SNIFF_LEN`] bytes, and is valid UTF-8 throughout.
+#[wasm_bindgen]
+pub fn is_probably_text(data: &[u8]) -> bool {
+    if data.is_empty() {
+        return true;
+    }
+    let sniffed = &data[..data.len().min(SNIFF_LEN)];
+    if sniffed.contains(&0) {
+        return false;
+    }
+    std::str::from_utf8(data).is_ok()
+}
+
+/// Pulls a note's lines one at a time instead of collecting them all into
+/// a `Vec<String>` up front, so scanning a multi-megabyte file for a
+/// pattern doesn't double its memory footprint just to iterate it.
+///
+/// Recognizes the same line endings as the rest of this module (`\r\n`,
+/// `\n`, or a lone `\r`). An empty string yields no lines, and a
+/// trailing line break doesn't produce a phantom empty final line.
+#[wasm_bindgen]
+pub struct LineReader {
+    text: String,
+    pos: usize,
+}
+
+#[wasm_bindgen]
+impl LineReader {
+    #[wasm_bindgen(constructor)]
+    pub fn new(text: &str) -> LineReader {
+        LineReader {
+            text: text.to_string(),
+            pos: 0,
+        }
+    }
+
+    /// The next line, without its line-ending characters, or `None` once
+    /// every line has been consumed.
+    pub fn next_line(&mut self) -> Option<String> {
+        let bytes = self.text.as_bytes();
+        if self.pos >= bytes.len() {
+            return None;
+        }
+
+        let mut i = self.pos;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\r' => {
+                    let line = self.text[self.pos..i].to_string();
+                    self.pos = i + if bytes.get(i + 1) == Some(&b'\n') { 2 } else { 1 };
+                    return Some(line);
+                }
+                b'\n' => {
+                    let line = self.text[self.pos..i].to_string();
+                    self.pos = i + 1;
+                    return Some(line);
+                }
+                _ => i += 1,
+            }
+        }
+
+        let line = self.text[self.pos..].to_string();
+        self.pos = bytes.len();
+        Some(line)
+    }
+}
+
+/// Count `text`'s lines without allocating them into a `Vec`, following
+/// the same rules as [`LineReader`]: an empty string is zero lines, and a
+/// trailing line break doesn't count a phantom empty final line.
+#[wasm_bindgen]
+pub fn line_count(text: &str) -> usize {
+    if text.is_empty() {
+        return 0;
+    }
+
+    let bytes = text.as_bytes();
+    let mut count = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\r' => {
+                count += 1;
+                i += if bytes.get(i + 1) == Some(&b'\n') { 2 } else { 1 };
+            }
+            b'\n' => {
+                count += 1;
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+
+    if !matches!(bytes[i - 1], b'\n' | b'\r') {
+        count += 1;
+    }
+
+    count
+}
+
+/// The result of [`sanitize_utf8`]: recovered valid UTF-8, plus whether
+/// any invalid byte sequence had to be replaced to get there.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SanitizedText {
+    text: String,
+    replaced: bool,
+}
+
+#[wasm_bindgen]
+impl SanitizedText {
+    #[wasm_bindgen(getter)]
+    pub fn text(&self) -> String {
+        self.text.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn replaced(&self) -> bool {
+        self.replaced
+    }
+}
+
+/// Recover a valid `&str` from `data`, which may contain invalid UTF-8
+/// (a corrupted file, or one written by an editor that doesn't guarantee
+/// well-formed encoding). Passing such bytes straight to a `&str`
+/// parameter traps the WASM call instead of returning an error, so
+/// callers reading untrusted file content off disk should sanitize it
+/// with this first.
+///
+/// Valid UTF-8 passes through byte-identical, with `replaced` false.
+/// Otherwise, each invalid sequence is replaced with U+FFFD (the
+/// standard Unicode replacement character), matching the same recovery
+/// [`String::from_utf8_lossy`] performs, and `replaced` is true.
+#[wasm_bindgen]
+pub fn sanitize_utf8(data: &[u8]) -> SanitizedText {
+    match std::str::from_utf8(data) {
+        Ok(text) => SanitizedText {
+            text: text.to_string(),
+            replaced: false,
+        },
+        Err(_) => SanitizedText {
+            text: String::from_utf8_lossy(data).into_owned(),
+            replaced: true,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn lf_style_converts_everything_to_lf() {
+        assert_eq!(normalize_line_endings("a\r\nb\rc\nd", "lf"), "a\nb\nc\nd");
+    }
+
+    #[wasm_bindgen_test]
+    fn crlf_style_converts_everything_to_crlf() {
+        assert_eq!(normalize_line_endings("a\r\nb\rc\nd", "crlf"), "a\r\nb\r\nc\r\nd");
+    }
+
+    #[wasm_bindgen_test]
+    fn auto_style_picks_the_dominant_ending() {
+        assert_eq!(normalize_line_endings("a\r\nb\r\nc\nd", "auto"), "a\r\nb\r\nc\r\nd");
+        assert_eq!(normalize_line_endings("a\nb\nc\r\nd", "auto"), "a\nb\nc\nd");
+    }
+
+    #[wasm_bindgen_test]
+    fn a_lone_cr_is_treated_as_a_line_break() {
+        assert_eq!(normalize_line_endings("a\rb", "lf"), "a\nb");
+    }
+
+    #[wasm_bindgen_test]
+    fn mixed_endings_normalize_for_hash_deterministically() {
+        let windows = "line one\r\nline two\r\nline three";
+        let unix = "line one\nline two\nline three";
+        let mixed = "line one\r\nline two\nline three";
+        assert_eq!(normalize_for_hash(windows), normalize_for_hash(unix));
+        assert_eq!(normalize_for_hash(windows), normalize_for_hash(mixed));
+    }
+
+    #[wasm_bindgen_test]
+    fn trailing_newline_is_preserved() {
+        assert_eq!(normalize_for_hash("a\r\nb\r\n"), "a\nb\n");
+        assert_eq!(normalize_for_hash("a\r\nb"), "a\nb");
+    }
+
+    #[wasm_bindgen_test]
+    fn unrecognized_style_falls_back_to_lf() {
+        assert_eq!(normalize_line_endings("a\r\nb", "weird"), "a\nb");
+    }
+
+    #[wasm_bindgen_test]
+    fn empty_input_is_treated_as_text() {
+        assert!(is_probably_text(&[]));
+    }
+
+    #[wasm_bindgen_test]
+    fn a_markdown_sample_is_text() {
+        let markdown = "# Title\n\nSome *notes* with a [[wikilink]].\n";
+        assert!(is_probably_text(markdown.as_bytes()));
+    }
+
+    #[wasm_bindgen_test]
+    fn utf8_with_emoji_is_text() {
+        let content = "Standup notes 🎉 — all good, no blockers.";
+        assert!(is_probably_text(content.as_bytes()));
+    }
+
+    #[wasm_bindgen_test]
+    fn a_png_header_is_not_text() {
+        let png = [0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d];
+        assert!(!is_probably_text(&png));
+    }
+
+    #[wasm_bindgen_test]
+    fn an_empty_string_yields_zero_lines() {
+        let mut reader = LineReader::new("");
+        assert_eq!(reader.next_line(), None);
+        assert_eq!(line_count(""), 0);
+    }
+
+    #[wasm_bindgen_test]
+    fn lf_lines_are_read_in_order() {
+        let mut reader = LineReader::new("one\ntwo\nthree");
+        assert_eq!(reader.next_line().as_deref(), Some("one"));
+        assert_eq!(reader.next_line().as_deref(), Some("two"));
+        assert_eq!(reader.next_line().as_deref(), Some("three"));
+        assert_eq!(reader.next_line(), None);
+        assert_eq!(line_count("one\ntwo\nthree"), 3);
+    }
+
+    #[wasm_bindgen_test]
+    fn crlf_lines_are_read_without_the_carriage_return() {
+        let mut reader = LineReader::new("one\r\ntwo\r\nthree");
+        assert_eq!(reader.next_line().as_deref(), Some("one"));
+        assert_eq!(reader.next_line().as_deref(), Some("two"));
+        assert_eq!(reader.next_line().as_deref(), Some("three"));
+        assert_eq!(reader.next_line(), None);
+        assert_eq!(line_count("one\r\ntwo\r\nthree"), 3);
+    }
+
+    #[wasm_bindgen_test]
+    fn a_missing_final_newline_still_yields_the_last_line() {
+        let mut reader = LineReader::new("one\ntwo");
+        assert_eq!(reader.next_line().as_deref(), Some("one"));
+        assert_eq!(reader.next_line().as_deref(), Some("two"));
+        assert_eq!(reader.next_line(), None);
+        assert_eq!(line_count("one\ntwo"), 2);
+    }
+
+    #[wasm_bindgen_test]
+    fn a_trailing_newline_does_not_produce_a_phantom_empty_line() {
+        let mut reader = LineReader::new("one\ntwo\n");
+        assert_eq!(reader.next_line().as_deref(), Some("one"));
+        assert_eq!(reader.next_line().as_deref(), Some("two"));
+        assert_eq!(reader.next_line(), None);
+        assert_eq!(line_count("one\ntwo\n"), 2);
+    }
+
+    #[wasm_bindgen_test]
+    fn valid_utf8_passes_through_byte_identical_and_unreplaced() {
+        let text = "Standup notes 🎉 — all good, no blockers.";
+        let sanitized = sanitize_utf8(text.as_bytes());
+        assert_eq!(sanitized.text(), text);
+        assert!(!sanitized.replaced());
+    }
+
+    #[wasm_bindgen_test]
+    fn invalid_bytes_become_the_replacement_character() {
+        let mut data = b"before".to_vec();
+        data.push(0xff);
+        data.extend_from_slice(b"after");
+
+        let sanitized = sanitize_utf8(&data);
+        assert_eq!(sanitized.text(), "before\u{fffd}after");
+        assert!(sanitized.replaced());
+    }
+}
@@ -0,0 +1,136 @@
+//! Track deletions, so a file removed on one device doesn't silently
+//! reappear the next time it syncs with a device that hasn't seen the
+//! deletion yet.
+//!
+//! Deletions are recorded by path and timestamp rather than diffed from
+//! two manifests, because a plain manifest diff can't tell "never
+//! existed here" apart from "existed and was deleted" — both look like
+//! "missing locally, present remotely".
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::error::SyncError;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TombstoneEntry {
+    path: String,
+    deleted_at: f64,
+}
+
+/// A log of deleted paths and when they were deleted.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TombstoneLog {
+    pub(crate) tombstones: BTreeMap<String, f64>,
+}
+
+#[wasm_bindgen]
+impl TombstoneLog {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> TombstoneLog {
+        TombstoneLog::default()
+    }
+
+    /// Record `path` as deleted at `deleted_at`, replacing any earlier
+    /// record for the same path.
+    pub fn record(&mut self, path: &str, deleted_at: f64) {
+        self.tombstones.insert(path.to_string(), deleted_at);
+    }
+
+    /// Was `path` deleted more recently than `since`? Used to decide
+    /// whether a remote copy still showing `path` is a stale re-add that
+    /// the deletion should win over, rather than a genuine new file.
+    pub fn is_deleted(&self, path: &str, since: f64) -> bool {
+        self.tombstones.get(path).is_some_and(|&deleted_at| deleted_at > since)
+    }
+
+    /// Drop every tombstone recorded before `before`, so the log doesn't
+    /// grow forever once every device has long since caught up on a
+    /// deletion.
+    pub fn prune(&mut self, before: f64) {
+        self.tombstones.retain(|_, &mut deleted_at| deleted_at >= before);
+    }
+
+    /// Serialize to JSON, sorted by path for deterministic output.
+    pub fn to_json(&self) -> String {
+        let sorted: Vec<TombstoneEntry> = self
+            .tombstones
+            .iter()
+            .map(|(path, &deleted_at)| TombstoneEntry { path: path.clone(), deleted_at })
+            .collect();
+        serde_json::to_string(&sorted).expect("TombstoneEntry serialization is infallible")
+    }
+
+    /// Parse a log previously produced by [`TombstoneLog::to_json`].
+    pub fn from_json(s: &str) -> Result<TombstoneLog, SyncError> {
+        crate::guard(|| {
+            let entries: Vec<TombstoneEntry> = serde_json::from_str(s)
+                .map_err(|err| SyncError::CorruptData(format!("invalid tombstone log JSON: {err}")))?;
+            let mut log = TombstoneLog::default();
+            for entry in entries {
+                log.tombstones.insert(entry.path, entry.deleted_at);
+            }
+            Ok(log)
+        })?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn a_recorded_deletion_suppresses_a_stale_re_add() {
+        let mut log = TombstoneLog::new();
+        log.record("deleted.md", 200.0);
+
+        assert!(log.is_deleted("deleted.md", 100.0));
+        assert!(!log.is_deleted("deleted.md", 250.0));
+        assert!(!log.is_deleted("never-deleted.md", 100.0));
+    }
+
+    #[wasm_bindgen_test]
+    fn recording_the_same_path_again_replaces_the_earlier_timestamp() {
+        let mut log = TombstoneLog::new();
+        log.record("a.md", 100.0);
+        log.record("a.md", 300.0);
+        assert!(log.is_deleted("a.md", 200.0));
+    }
+
+    #[wasm_bindgen_test]
+    fn pruning_removes_expired_entries() {
+        let mut log = TombstoneLog::new();
+        log.record("old.md", 100.0);
+        log.record("recent.md", 500.0);
+
+        log.prune(300.0);
+
+        assert!(!log.is_deleted("old.md", 0.0));
+        assert!(log.is_deleted("recent.md", 0.0));
+    }
+
+    #[wasm_bindgen_test]
+    fn from_json_round_trips_to_json() {
+        let mut log = TombstoneLog::new();
+        log.record("b.md", 2.0);
+        log.record("a.md", 1.0);
+
+        let json = log.to_json();
+        let reparsed = TombstoneLog::from_json(&json).unwrap();
+        assert_eq!(reparsed.to_json(), json);
+        assert!(json.find("a.md").unwrap() < json.find("b.md").unwrap());
+    }
+
+    #[wasm_bindgen_test]
+    fn from_json_rejects_malformed_input_with_a_clear_error() {
+        let err = TombstoneLog::from_json("not json").unwrap_err();
+        match err {
+            SyncError::CorruptData(message) => assert!(message.contains("invalid tombstone log JSON")),
+            other => panic!("expected SyncError::CorruptData, got {other:?}"),
+        }
+    }
+}
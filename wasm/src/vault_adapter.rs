@@ -0,0 +1,133 @@
+//! `extern "C"` bindings to Obsidian's vault storage adapter (`VaultAdapter`),
+//! plus the two async entry points built on it: `sync_vault`, which reads
+//! local files and turns changes into upload-ready patches, and
+//! `apply_remote_patch`, which applies a patch received from the remote
+//! side and writes the result back through the adapter.
+
+use js_sys::{Object, Reflect, Uint8Array};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::delta::{apply_delta, compute_delta};
+use crate::hashing::content_hash;
+
+/// Metadata for a single vault entry, as tracked by [`sync_vault`]'s
+/// MessagePack-encoded manifest. Distinct from [`crate::manifest::Manifest`],
+/// which is the JSON-based manifest the sync planner (diff/rename
+/// detection) operates on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct NoteMeta {
+    path: String,
+    content_id: String,
+    size: u64,
+    mtime: u64,
+    tags: Vec<String>,
+}
+
+/// A full vault manifest: one [`NoteMeta`] per entry.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct SyncManifest {
+    entries: Vec<NoteMeta>,
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// Mirrors the subset of Obsidian's `DataAdapter` the sync engine needs.
+    pub type VaultAdapter;
+
+    #[wasm_bindgen(method, catch)]
+    async fn read(this: &VaultAdapter, path: &str) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    async fn write(this: &VaultAdapter, path: &str, bytes: &Uint8Array) -> Result<(), JsValue>;
+
+    /// Vault-relative paths of every file the adapter currently knows about.
+    #[wasm_bindgen(method)]
+    fn list(this: &VaultAdapter) -> Vec<String>;
+
+    #[wasm_bindgen(method, catch)]
+    async fn stat(this: &VaultAdapter, path: &str) -> Result<JsValue, JsValue>;
+}
+
+/// Result of a `sync_vault` pass: the refreshed manifest plus one
+/// rsync-style patch (see [`crate::delta`]) per changed or new file, keyed
+/// by vault path, ready to upload to the remote sync server.
+#[wasm_bindgen(getter_with_clone)]
+pub struct SyncResult {
+    pub manifest: Vec<u8>,
+    pub patches: Object,
+}
+
+/// List the files behind `adapter`, hash each one, and diff against
+/// `previous_manifest` (a MessagePack-encoded [`SyncManifest`] from the last
+/// sync, or an empty buffer for a first run). `previous_contents` is a
+/// plain JS object mapping vault path to the `Uint8Array` bytes last
+/// synced for that path; it's used as the delta base for changed files
+/// (a missing or unknown path falls back to an empty base, so the whole
+/// file goes out as one literal run). Returns the refreshed manifest
+/// together with one patch per changed or new file.
+#[wasm_bindgen]
+pub async fn sync_vault(
+    adapter: &VaultAdapter,
+    previous_manifest: &[u8],
+    previous_contents: &Object,
+) -> Result<SyncResult, JsValue> {
+    let previous: SyncManifest = if previous_manifest.is_empty() {
+        SyncManifest::default()
+    } else {
+        rmp_serde::from_slice(previous_manifest).map_err(|err| JsValue::from_str(&err.to_string()))?
+    };
+
+    let mut next = SyncManifest::default();
+    let patches = Object::new();
+
+    for path in adapter.list() {
+        let raw = adapter.read(&path).await?;
+        let bytes = Uint8Array::new(&raw).to_vec();
+        let id = content_hash(&bytes);
+
+        let previous_entry = previous.entries.iter().find(|entry| entry.path == path);
+        let changed = previous_entry.map(|entry| entry.content_id != id).unwrap_or(true);
+
+        if changed {
+            let base = Reflect::get(previous_contents, &JsValue::from_str(&path))
+                .ok()
+                .filter(|value| !value.is_undefined())
+                .map(|value| Uint8Array::new(&value).to_vec())
+                .unwrap_or_default();
+            let patch = compute_delta(&base, &bytes);
+            Reflect::set(&patches, &JsValue::from_str(&path), &Uint8Array::from(patch.as_slice()))?;
+        }
+
+        let stat = adapter.stat(&path).await?;
+        let mtime = Reflect::get(&stat, &JsValue::from_str("mtime"))
+            .ok()
+            .and_then(|value| value.as_f64())
+            .unwrap_or(0.0) as u64;
+
+        next.entries.push(NoteMeta {
+            path,
+            content_id: id,
+            size: bytes.len() as u64,
+            mtime,
+            tags: previous_entry.map(|entry| entry.tags.clone()).unwrap_or_default(),
+        });
+    }
+
+    let manifest = rmp_serde::to_vec(&next).map_err(|err| JsValue::from_str(&err.to_string()))?;
+    Ok(SyncResult { manifest, patches })
+}
+
+/// Apply a patch received from the remote sync server — produced there
+/// against `base`, the bytes last synced for `path` — and write the
+/// reconstructed file back through `adapter`.
+#[wasm_bindgen]
+pub async fn apply_remote_patch(
+    adapter: &VaultAdapter,
+    path: &str,
+    base: &[u8],
+    patch: &[u8],
+) -> Result<(), JsValue> {
+    let bytes = apply_delta(base, patch)?;
+    adapter.write(path, &Uint8Array::from(bytes.as_slice())).await
+}